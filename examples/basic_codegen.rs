@@ -7,10 +7,11 @@ macro_rules! new_module {
 				pub const a: usize = 0;
 				pub const b: usize = 10;
 				
-				// The `#POINT_TRACKER_FILES:` marker allows the macro to add additional 
-				// instructions that tell the compiler which files to track so that it can 
-				// recompile the macro if they change. This is completely optional, but without 
-				// it tracking will not work.
+				// The macro adds instructions that tell the compiler which files to track 
+				// so that it can recompile the macro if they change. The optional 
+				// `#POINT_TRACKER_FILES:` marker sets where they are placed (by default, 
+				// before the result of the macro if it is items or statements,
+				// or in a block with it if it is an expression).
 				#POINT_TRACKER_FILES: 
 				
 				pub const $const_t: (usize, usize) = (#tt($($path)*));
@@ -0,0 +1,115 @@
+use crate::{throw_sg_err, trees::result::TreeResult};
+use alloc::{
+	borrow::Cow,
+	string::{String, ToString},
+	vec::Vec,
+};
+use proc_macro2::Span;
+use std::path::{Path, PathBuf};
+
+/// The maximum nesting of files included with `#tt[recursive]`.
+pub const MAX_DEPTH: usize = 16;
+
+/// Files whose contents are currently being searched for directives,
+/// from the outermost one, `#tt[recursive]("a.tt")`.
+#[derive(Debug, Default)]
+pub struct IncludeChain {
+	files: Vec<PathBuf>,
+}
+
+impl IncludeChain {
+	/// Resolve the path of the directive: inside an included file,
+	/// relative paths are relative to the directory of this file.
+	pub fn resolve<'a>(&self, path: &'a str) -> Cow<'a, str> {
+		match self.files.last().and_then(|a| a.parent()) {
+			Some(dir) if Path::new(path).is_relative() => {
+				Cow::Owned(dir.join(path).to_string_lossy().into_owned())
+			}
+			_ => Cow::Borrowed(path),
+		}
+	}
+
+	/// Description of the chain for error messages: `a.tt -> b.tt -> a.tt`.
+	fn describe(&self, next: &Path) -> String {
+		let mut result = String::new();
+		for file in self.files.iter().map(|a| a.as_path()).chain([next]) {
+			if !result.is_empty() {
+				result.push_str(" -> ");
+			}
+			result.push_str(&file.display().to_string());
+		}
+
+		result
+	}
+
+	/// Enter the included file, checking the depth and that the file
+	/// does not include itself (directly or through other files).
+	pub fn enter(&mut self, path: &Path, span: Span) -> TreeResult<()> {
+		let canonicalize = |a: &Path| a.canonicalize().unwrap_or_else(|_| a.to_path_buf());
+
+		let cpath = canonicalize(path);
+		if self.files.iter().any(|a| canonicalize(a) == cpath) {
+			let chain = self.describe(path);
+			throw_sg_err! {
+				return [span]: "Cyclic inclusion of files: ", #chain, "."
+			}
+		}
+		if self.files.len() >= MAX_DEPTH {
+			let chain = self.describe(path);
+			let depth = MAX_DEPTH.to_string();
+			throw_sg_err! {
+				return [span]: "The maximum depth of recursive inclusion (", #depth, ") was exceeded: ", #chain, "."
+			}
+		}
+
+		self.files.push(path.to_path_buf());
+		TreeResult::Ok(())
+	}
+
+	/// Leave the file entered with `enter`.
+	#[inline]
+	pub fn leave(&mut self) {
+		self.files.pop();
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_include_chain() {
+	let span = Span::call_site();
+	let mut chain = IncludeChain::default();
+	assert_eq!(chain.resolve("a.tt"), "a.tt");
+
+	assert!(matches!(
+		chain.enter(Path::new("dir/a.tt"), span),
+		TreeResult::Ok(())
+	));
+	assert_eq!(chain.resolve("b.tt"), "dir/b.tt");
+	assert_eq!(chain.resolve("/b.tt"), "/b.tt");
+
+	assert!(matches!(
+		chain.enter(Path::new("dir/b.tt"), span),
+		TreeResult::Ok(())
+	));
+	assert_eq!(
+		chain.describe(Path::new("dir/a.tt")),
+		"dir/a.tt -> dir/b.tt -> dir/a.tt"
+	);
+	assert!(matches!(
+		chain.enter(Path::new("dir/a.tt"), span),
+		TreeResult::Err(..)
+	));
+
+	chain.leave();
+	chain.leave();
+	for i in 0..MAX_DEPTH {
+		assert!(matches!(
+			chain.enter(Path::new(&format!("{i}.tt")), span),
+			TreeResult::Ok(())
+		));
+	}
+	assert!(matches!(
+		chain.enter(Path::new("last.tt"), span),
+		TreeResult::Err(..)
+	));
+}
@@ -0,0 +1,126 @@
+use crate::{
+	SearchState,
+	exprs::args::is_punct,
+	throw_sg_err, track_file, tracked_env_var,
+	trees::{group::stream_stringify_with_fns, result::TreeResult, tq},
+	vars::Vars,
+};
+use alloc::string::String;
+use proc_macro2::{Delimiter, Span, TokenStream as TokenStream2, TokenTree as TokenTree2};
+use std::path::Path;
+use syn::LitStr;
+
+/// Get the string argument of the condition function: `exists(#DIR "x.tt")`.
+fn stringify_arg(args: TokenStream2, vars: &Vars, span: Span) -> TreeResult<String> {
+	stream_stringify_with_fns(
+		vars.substitute(args),
+		TreeResult::Ok,
+		|| {
+			TreeResult::Err(throw_sg_err! {
+				[span]: "The argument of the condition was expected: `exists(\"x.tt\")`."
+			})
+		},
+		TreeResult::Err,
+	)
+}
+
+/// Compute the condition of `#if COND { ... }`.
+///
+/// ```text
+/// COND := `!` COND
+/// 	| `exists(path)`
+/// 	| `defined(NAME)`
+/// 	| `env(name)` [(`==` | `!=`) "value"]
+/// ```
+///
+/// (The variables of `env` and the existing files of `exists` are tracked,
+/// creating a missing file does not cause a rebuild)
+pub fn eval_condition(
+	trees: &[TokenTree2],
+	state: &mut SearchState,
+	span: Span,
+) -> TreeResult<bool> {
+	let (name, args, cmp) = match trees {
+		[not, cond @ ..] if is_punct(not, '!') => {
+			return match eval_condition(cond, state, not.span()) {
+				TreeResult::Ok(a) => TreeResult::Ok(!a),
+				TreeResult::Err(e) => TreeResult::Err(e),
+			};
+		}
+		[TokenTree2::Ident(name), TokenTree2::Group(args), cmp @ ..]
+			if args.delimiter() == Delimiter::Parenthesis =>
+		{
+			(name, args, cmp)
+		}
+		[tt, ..] => throw_sg_err! {
+			return [tt.span()]: "The condition was expected: `exists(\"x.tt\")`, `defined(NAME)`, `env(\"NAME\")`, `env(\"NAME\") == \"value\"`."
+		},
+		[] => throw_sg_err! {
+			return [span]: "The condition was expected after `#if`."
+		},
+	};
+
+	let value = match name {
+		name if name == "exists" => {
+			let path = tq!(stringify_arg(args.stream(), state.vars, args.span()));
+			let path = state.chain.resolve(&path);
+			let path = Path::new(&*path);
+
+			return match cmp {
+				[] if path.is_file() => {
+					track_file(state.point_track_file.as_mut(), path);
+					TreeResult::Ok(true)
+				}
+				[] => TreeResult::Ok(path.exists()),
+				[tt, ..] => throw_sg_err! {
+					return [tt.span()]: "`exists(..)` cannot be compared."
+				},
+			};
+		}
+		name if name == "defined" => {
+			let mut iter = args.stream().into_iter();
+			return match (iter.next(), iter.next(), cmp) {
+				(Some(TokenTree2::Ident(name)), None, []) => {
+					TreeResult::Ok(state.vars.get(&name).is_some())
+				}
+				(_, _, [tt, ..]) => throw_sg_err! {
+					return [tt.span()]: "`defined(..)` cannot be compared."
+				},
+				_ => throw_sg_err! {
+					return [args.span()]: "The name was expected: `defined(NAME)`."
+				},
+			};
+		}
+		name if name == "env" => {
+			let key = tq!(stringify_arg(args.stream(), state.vars, args.span()));
+
+			tracked_env_var(state.point_track_file.as_mut(), &key)
+		}
+		name => throw_sg_err! {
+			return [name.span()]: "Unknown condition, expected: `exists`, `defined`, `env`."
+		},
+	};
+
+	match cmp {
+		[] => TreeResult::Ok(value.is_some()),
+		[op, eq, TokenTree2::Literal(lit)]
+			if is_punct(eq, '=') && (is_punct(op, '=') || is_punct(op, '!')) =>
+		{
+			let exp = match syn::parse2::<LitStr>(TokenTree2::Literal(lit.clone()).into()) {
+				Ok(a) => a.value(),
+				Err(..) => throw_sg_err! {
+					return [lit.span()]: "A string was expected for comparison."
+				},
+			};
+
+			let is_eq = value.is_some_and(|a| a == exp);
+			TreeResult::Ok(match is_punct(op, '=') {
+				true => is_eq,
+				false => !is_eq,
+			})
+		}
+		[tt, ..] => throw_sg_err! {
+			return [tt.span()]: "The comparison was expected: `== \"value\"` or `!= \"value\"`."
+		},
+	}
+}
@@ -0,0 +1,192 @@
+use crate::{exprs::kind::SyntaxKind, throw_sg_err, trees::result::TreeResult};
+use alloc::vec::Vec;
+use proc_macro2::{
+	Group, Ident, Spacing, Span, TokenStream as TokenStream2, TokenTree as TokenTree2,
+};
+
+/// Additional arguments of the directive written next to the path.
+#[derive(Debug, Clone, Default)]
+pub struct IncludeArgs {
+	/// What the contents of the file must parse as, `#tt(expr: "x.tt")`.
+	pub kind: SyntaxKind,
+
+	/// Flags written in brackets before the path: `#tt[paren]("x.tt")`.
+	pub flags: Vec<Ident>,
+
+	/// Options written after `;`: `#str("a.sql", "b.sql"; sep = "\n")`.
+	pub options: Vec<(Ident, TokenStream2)>,
+
+	/// The span of the path being included (see `path_span`), errors
+	/// about the file point at it.
+	pub path_span: Option<PathSpan>,
+}
+
+/// The span of an error about the path, from its first to its last tree.
+#[derive(Debug, Clone, Copy)]
+pub struct PathSpan {
+	pub first: Span,
+	pub last: Span,
+}
+
+impl From<Span> for PathSpan {
+	#[inline]
+	fn from(span: Span) -> Self {
+		Self {
+			first: span,
+			last: span,
+		}
+	}
+}
+
+impl PathSpan {
+	/// Make the `compile_error!(..);` of `throw_sg_err` span the whole path:
+	/// the error spans from the name of the macro to its arguments (which
+	/// works where `Span::join` is not available, on stable).
+	pub fn apply(self, e: TokenStream2) -> TokenStream2 {
+		let mut trees: Vec<TokenTree2> = e.into_iter().collect();
+		for chunk in trees.chunks_mut(4) {
+			if let [
+				TokenTree2::Ident(name),
+				TokenTree2::Punct(bang),
+				TokenTree2::Group(args),
+				TokenTree2::Punct(semi),
+			] = chunk && name == "compile_error"
+			{
+				name.set_span(self.first);
+				bang.set_span(self.first);
+				args.set_span(self.last);
+				semi.set_span(self.last);
+			}
+		}
+
+		TokenStream2::from_iter(trees)
+	}
+}
+
+/// The span of the path for errors: all of its trees (`"dir/" "a.tt"` in
+/// `#tt("dir/" "a.tt")`), joined if possible, or the first and the last one.
+pub fn path_span(path: &TokenStream2) -> Option<PathSpan> {
+	let mut spans = path.clone().into_iter().map(|a| a.span());
+	let first = spans.next()?;
+	let last = spans.last().unwrap_or(first);
+
+	Some(match first.join(last) {
+		Some(span) => PathSpan::from(span),
+		None => PathSpan { first, last },
+	})
+}
+
+/// Returns `true` if the tree is the given punctuation character.
+#[inline]
+pub fn is_punct(tt: &TokenTree2, c: char) -> bool {
+	matches!(tt, TokenTree2::Punct(p) if p.as_char() == c)
+}
+
+impl IncludeArgs {
+	/// Separate the arguments from the paths and return the trees of each path.
+	///
+	/// `[flag[, flag]*] ([kind:] path[, path]* [; name = value[, name = value]*])`
+	pub fn parse(
+		flags: Option<&Group>,
+		stream: TokenStream2,
+	) -> TreeResult<(Self, Vec<TokenStream2>)> {
+		let mut args = Self::default();
+
+		if let Some(flags) = flags {
+			for tt in flags.stream() {
+				match tt {
+					TokenTree2::Ident(flag) => args.flags.push(flag),
+					tt if is_punct(&tt, ',') => {}
+					tt => throw_sg_err! {
+						return [tt.span()]: "The name of the flag was expected: `[flag, flag]`."
+					},
+				}
+			}
+		}
+
+		let all: Vec<TokenTree2> = stream.into_iter().collect();
+		let (paths, options) = match all.iter().position(|a| is_punct(a, ';')) {
+			Some(pos) => (&all[..pos], &all[pos + 1..]),
+			None => (&all[..], &[] as &[_]),
+		};
+
+		let paths = match paths {
+			// `kind: path`
+			[TokenTree2::Ident(name), TokenTree2::Punct(colon), path @ ..]
+				if colon.as_char() == ':' && colon.spacing() == Spacing::Alone =>
+			{
+				match SyntaxKind::from_name(&name.to_string()) {
+					Some(kind) if cfg!(feature = "syntax_kinds") => {
+						args.kind = kind;
+						path
+					}
+					Some(..) => throw_sg_err! {
+						return [name.span()]: "The syntax kind before the path (`expr:`, `items:`, ...) requires the `syntax_kinds` feature of `include_tt`."
+					},
+					None => paths,
+				}
+			}
+			_ => paths,
+		};
+		let mut paths: Vec<TokenStream2> = paths
+			.split(|a| is_punct(a, ','))
+			.map(|a| TokenStream2::from_iter(a.iter().cloned()))
+			.collect();
+		// A trailing comma is allowed: `"a.tt", "b.tt",`.
+		if paths.len() > 1 && paths.last().is_some_and(|a| a.is_empty()) {
+			paths.pop();
+		}
+
+		let mut iter = options.iter().peekable();
+		while let Some(tt) = iter.next() {
+			let name = match tt {
+				TokenTree2::Ident(name) => name.clone(),
+				tt => throw_sg_err! {
+					return [tt.span()]: "The name of the option was expected: `name = value`."
+				},
+			};
+			match iter.next() {
+				Some(tt) if is_punct(tt, '=') => {}
+				_ => throw_sg_err! {
+					return [name.span()]: "`=` was expected after the name of the option."
+				},
+			}
+
+			// The value takes at least one tree, so `sep = ,` is also possible.
+			let mut value = Vec::new();
+			match iter.next() {
+				Some(tt) => value.push(tt.clone()),
+				None => throw_sg_err! {
+					return [name.span()]: "The value of the option was expected after `=`."
+				},
+			}
+			while let Some(tt) = iter.next_if(|a| !is_punct(a, ',') && !is_punct(a, ';')) {
+				value.push(tt.clone());
+			}
+			iter.next_if(|a| is_punct(a, ',') || is_punct(a, ';'));
+
+			args.options.push((name, TokenStream2::from_iter(value)));
+		}
+
+		TreeResult::Ok((args, paths))
+	}
+
+	/// Returns `true` if the flag is set.
+	pub fn has_flag(&self, name: &str) -> bool {
+		self.flags.iter().any(|a| a == name)
+	}
+
+	/// The span for errors about the file: the path, or the directive if unknown.
+	#[inline]
+	pub fn err_span(&self, span: Span) -> PathSpan {
+		self.path_span.unwrap_or(PathSpan::from(span))
+	}
+
+	/// Get the value of the option by name.
+	pub fn option(&self, name: &str) -> Option<&TokenStream2> {
+		self.options
+			.iter()
+			.find(|(a, _)| a == name)
+			.map(|(_, value)| value)
+	}
+}
@@ -0,0 +1,99 @@
+use crate::throw_sg_err;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree as TokenTree2};
+
+/// Strict and reserved keywords of the language (all editions).
+const KEYWORDS: &[&str] = &[
+	"as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+	"if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+	"self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+	"while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+	"override", "priv", "typeof", "unsized", "virtual", "yield", "try", "gen",
+];
+
+/// Keywords that cannot be used even as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super", "_"];
+
+/// Returns `true` if the string is a keyword of the language.
+#[inline]
+pub fn is_keyword(a: &str) -> bool {
+	KEYWORDS.contains(&a)
+}
+
+/// Errors received when building an identifier from a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MakeIdentErr<'a> {
+	/// The string does not form an identifier.
+	InvalidChars(&'a str),
+
+	/// The string is a keyword, `r#` is required.
+	Keyword(&'a str),
+
+	/// The keyword cannot be a raw identifier.
+	RawKeyword(&'a str),
+}
+
+impl MakeIdentErr<'_> {
+	/// Convert an error to a syntax tree.
+	pub fn into_tt_err(self, span: Span) -> TokenStream2 {
+		match self {
+			Self::InvalidChars(a) => throw_sg_err! {
+				[span]: "`", #a, "` is not a valid identifier, only letters, digits and `_` are allowed, and it must not start with a digit."
+			},
+			Self::Keyword(a) => throw_sg_err! {
+				[span]: "`", #a, "` is a keyword, use `r#", #a, "` to get a raw identifier."
+			},
+			Self::RawKeyword(a) => throw_sg_err! {
+				[span]: "`", #a, "` cannot be a raw identifier."
+			},
+		}
+	}
+}
+
+/// Build an identifier from a string, `r#name` creates a raw identifier.
+pub fn make_ident(a: &str, span: Span) -> Result<Ident, MakeIdentErr<'_>> {
+	let (is_raw, name) = match a.strip_prefix("r#") {
+		Some(name) => (true, name),
+		None => (false, a),
+	};
+
+	// We leave the validation of characters to the lexer,
+	// the result must be exactly one identifier.
+	let mut iter = match name.parse::<TokenStream2>() {
+		Ok(a) => a.into_iter(),
+		Err(..) => return Err(MakeIdentErr::InvalidChars(a)),
+	};
+	match (iter.next(), iter.next()) {
+		(Some(TokenTree2::Ident(i)), None) if i == name => {}
+		_ => return Err(MakeIdentErr::InvalidChars(a)),
+	}
+
+	match is_raw {
+		true if NON_RAW_KEYWORDS.contains(&name) => Err(MakeIdentErr::RawKeyword(name)),
+		true => Ok(Ident::new_raw(name, span)),
+		false if is_keyword(name) => Err(MakeIdentErr::Keyword(name)),
+		false => Ok(Ident::new(name, span)),
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_make_ident() {
+	let span = Span::call_site();
+
+	assert_eq!(make_ident("my_fn", span).unwrap(), "my_fn");
+	assert_eq!(make_ident("r#type", span).unwrap(), "r#type");
+	assert_eq!(
+		make_ident("my-fn", span),
+		Err(MakeIdentErr::InvalidChars("my-fn"))
+	);
+	assert_eq!(
+		make_ident("1fn", span),
+		Err(MakeIdentErr::InvalidChars("1fn"))
+	);
+	assert_eq!(make_ident("", span), Err(MakeIdentErr::InvalidChars("")));
+	assert_eq!(make_ident("type", span), Err(MakeIdentErr::Keyword("type")));
+	assert_eq!(
+		make_ident("r#self", span),
+		Err(MakeIdentErr::RawKeyword("self"))
+	);
+}
@@ -0,0 +1,319 @@
+use crate::formats::lenient::PUNCT_CHARS;
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::parse::{ParseStream, Parser};
+#[cfg(feature = "syntax_kinds")]
+use syn::{Block, Expr, Item, Pat, Type};
+
+/// What the contents of the included file must parse as,
+/// written before the path: `#tt(expr: "x.tt")`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyntaxKind {
+	/// Any sequence of trees (the default behavior).
+	#[default]
+	TokenTrees,
+	/// `expr:`, exactly one expression.
+	Expr,
+	/// `items:`, zero or more items (`fn`, `struct`, `mod`...).
+	Items,
+	/// `stmts:`, zero or more statements, as inside a block.
+	Stmts,
+	/// `type:`, exactly one type.
+	Type,
+	/// `pat:`, exactly one pattern (`|` alternatives are allowed).
+	Pat,
+}
+
+impl SyntaxKind {
+	/// Get the kind by its name in the directive.
+	pub fn from_name(a: &str) -> Option<Self> {
+		Some(match a {
+			"expr" => Self::Expr,
+			"items" => Self::Items,
+			"stmts" => Self::Stmts,
+			"type" => Self::Type,
+			"pat" => Self::Pat,
+			_ => return None,
+		})
+	}
+
+	/// Description of the expected syntax for error messages.
+	pub const fn description(self) -> &'static str {
+		match self {
+			Self::TokenTrees => "token trees",
+			Self::Expr => "an expression",
+			Self::Items => "items",
+			Self::Stmts => "statements",
+			Self::Type => "a type",
+			Self::Pat => "a pattern",
+		}
+	}
+
+	/// Check that the stream matches the kind.
+	#[cfg(feature = "syntax_kinds")]
+	fn validate(self, input: ParseStream) -> syn::Result<()> {
+		match self {
+			Self::TokenTrees => {
+				input.parse::<TokenStream2>()?;
+			}
+			Self::Expr => {
+				input.parse::<Expr>()?;
+			}
+			Self::Items => {
+				while !input.is_empty() {
+					input.parse::<Item>()?;
+				}
+			}
+			Self::Stmts => {
+				Block::parse_within(input)?;
+			}
+			Self::Type => {
+				input.parse::<Type>()?;
+			}
+			Self::Pat => {
+				Pat::parse_multi_with_leading_vert(input)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Without the `syntax_kinds` feature only token trees are accepted
+	/// (the other kinds are rejected when the arguments are parsed).
+	#[cfg(not(feature = "syntax_kinds"))]
+	fn validate(self, input: ParseStream) -> syn::Result<()> {
+		input.parse::<TokenStream2>()?;
+
+		Ok(())
+	}
+
+	/// Parse the text into trees, checking that they match the kind.
+	pub fn parse_str(self, data: &str) -> syn::Result<TokenStream2> {
+		let stream: TokenStream2 = syn::parse_str(data)?;
+		if self != Self::TokenTrees {
+			(|input: ParseStream| self.validate(input)).parse2(stream.clone())?;
+		}
+
+		Ok(stream)
+	}
+
+	/// Find the byte position of the error in the text: the first character
+	/// the Rust lexer does not accept, or else the token ending the shortest
+	/// prefix of the text (with its brackets closed) that fails to parse with
+	/// the same error as the whole text.
+	///
+	/// (Only used on the error path, the prefixes are parsed by halving)
+	pub fn locate_err(self, data: &str) -> Option<usize> {
+		let message = self.parse_str(data).err()?.to_string();
+		let tokens = match scan_tokens(data) {
+			Ok(tokens) => tokens,
+			Err(position) => return Some(position),
+		};
+
+		let fails = |end: usize| {
+			let mut text = String::from(&data[..end]);
+			let mut closers = Vec::new();
+			for a in tokens.iter().take_while(|a| a.end <= end) {
+				match &data[a.clone()] {
+					"(" => closers.push(')'),
+					"[" => closers.push(']'),
+					"{" => closers.push('}'),
+					")" | "]" | "}" => {
+						closers.pop();
+					}
+					_ => {}
+				}
+			}
+			text.extend(closers.iter().rev());
+
+			self.parse_str(&text)
+				.is_err_and(|e| e.to_string() == message)
+		};
+		let first = tokens.partition_point(|a| !fails(a.end));
+
+		Some(match tokens.get(first) {
+			// Something is missing after the token (after the whole group if
+			// it opens one, its prefix is closed the same way).
+			Some(a) if message.starts_with("unexpected end of input") => {
+				let mut depth = 0;
+				tokens[first..]
+					.iter()
+					.find(|a| {
+						match &data[(*a).clone()] {
+							"(" | "[" | "{" => depth += 1,
+							")" | "]" | "}" => depth -= 1,
+							_ => {}
+						}
+						depth <= 0
+					})
+					.map_or(a.end, |a| a.end)
+			}
+			Some(a) => a.start,
+			None => tokens.last().map_or(0, |a| a.end),
+		})
+	}
+}
+
+/// Split the text into the byte ranges of its tokens (skipping comments) as
+/// the Rust lexer does, or return the position of the first lexing error:
+/// an unknown character, an unclosed literal or comment, an unbalanced bracket.
+fn scan_tokens(data: &str) -> Result<Vec<Range<usize>>, usize> {
+	let is_ident_char = |a: char| a == '_' || a.is_alphanumeric();
+	let char_at = |i: usize| data.get(i..).and_then(|a| a.chars().next());
+	// The end of the identifier characters starting at `i`.
+	let ident_end = |i: usize| {
+		data[i..]
+			.find(|a| !is_ident_char(a))
+			.map_or(data.len(), |a| i + a)
+	};
+	// The end of `"a"` or `'a'` starting at `i`, with its suffix.
+	let quoted_end = |i: usize, quote: char| {
+		let mut chars = data[i + 1..].char_indices();
+		while let Some((j, a)) = chars.next() {
+			match a {
+				'\\' => {
+					chars.next();
+				}
+				a if a == quote => return Some(ident_end(i + 1 + j + 1)),
+				_ => {}
+			}
+		}
+
+		None
+	};
+	// The end of `#"a"#` starting at `i` (after `r`), with its suffix.
+	let raw_end = |i: usize| {
+		let hashes = data[i..].len() - data[i..].trim_start_matches('#').len();
+		if char_at(i + hashes) != Some('"') {
+			return None;
+		}
+		let close = alloc::format!("\"{}", "#".repeat(hashes));
+		let start = i + hashes + 1;
+		data[start..]
+			.find(&close)
+			.map(|a| ident_end(start + a + close.len()))
+	};
+	// The end of the (nested) block comment starting at `i`.
+	let comment_end = |i: usize| {
+		let mut depth = 0;
+		let mut j = i;
+		while let Some(a) = char_at(j) {
+			if data[j..].starts_with("/*") {
+				depth += 1;
+				j += 2;
+			} else if data[j..].starts_with("*/") {
+				depth -= 1;
+				j += 2;
+				if depth == 0 {
+					return Some(j);
+				}
+			} else {
+				j += a.len_utf8();
+			}
+		}
+
+		None
+	};
+
+	let mut tokens = Vec::new();
+	let mut stack = Vec::new();
+	let mut i = 0;
+	while let Some(c) = char_at(i) {
+		let next = char_at(i + c.len_utf8());
+		let end = match c {
+			c if c.is_whitespace() => {
+				i += c.len_utf8();
+				continue;
+			}
+			'/' if next == Some('/') => {
+				i = data[i..].find('\n').map_or(data.len(), |a| i + a);
+				continue;
+			}
+			'/' if next == Some('*') => {
+				i = comment_end(i).ok_or(i)?;
+				continue;
+			}
+			'(' | '[' | '{' => {
+				stack.push((c, i));
+				i + 1
+			}
+			')' | ']' | '}' => match stack.pop() {
+				Some(('(', _)) if c == ')' => i + 1,
+				Some(('[', _)) if c == ']' => i + 1,
+				Some(('{', _)) if c == '}' => i + 1,
+				_ => return Err(i),
+			},
+			c if PUNCT_CHARS.contains(c) => i + 1,
+			'"' => quoted_end(i, '"').ok_or(i)?,
+			'\'' if next.is_some_and(is_ident_char) => {
+				let end = ident_end(i + 1);
+				match char_at(end) {
+					// `'a'`.
+					Some('\'') => end + 1,
+					// `'a` lifetime.
+					_ => end,
+				}
+			}
+			'\'' => quoted_end(i, '\'').ok_or(i)?,
+			c if is_ident_char(c) => {
+				let mut end = ident_end(i);
+				// `1.5`, `1e-3`, `2.5E+10f64`.
+				while c.is_ascii_digit() {
+					let number = &data[i..end];
+					match char_at(end) {
+						Some('.')
+							if !number.contains('.')
+								&& char_at(end + 1).is_some_and(|a| a.is_ascii_digit()) =>
+						{
+							end = ident_end(end + 1)
+						}
+						Some('+' | '-')
+							if number.ends_with(['e', 'E']) && !number.starts_with("0x") =>
+						{
+							end = ident_end(end + 1)
+						}
+						_ => break,
+					}
+				}
+
+				match (&data[i..end], char_at(end)) {
+					("b" | "c", Some('"')) => quoted_end(end, '"').ok_or(i)?,
+					("b", Some('\'')) => quoted_end(end, '\'').ok_or(i)?,
+					("r" | "br" | "cr", Some('"' | '#')) => match raw_end(end) {
+						Some(end) => end,
+						// `r#ident`.
+						None if char_at(end + 1).is_some_and(is_ident_char) => ident_end(end + 1),
+						None => return Err(i),
+					},
+					_ => end,
+				}
+			}
+			_ => return Err(i),
+		};
+
+		tokens.push(i..end);
+		i = end;
+	}
+
+	match stack.pop() {
+		// The innermost bracket that is never closed.
+		Some((_, open)) => Err(open),
+		None => Ok(tokens),
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_scan_tokens() {
+	let scan = |a: &str| scan_tokens(a).map(|a| a.len());
+
+	assert_eq!(scan("a + 1.5e-3 'b' 'c"), Ok(5));
+	assert_eq!(scan("r#\"a\"# b\"c\" r#type /* a /* b */ */ // `"), Ok(3));
+	assert_eq!(scan("f(a, [b]) { c }"), Ok(11));
+	assert_eq!(scan("a `b`"), Err(2));
+	assert_eq!(scan("a \"b"), Err(2));
+	assert_eq!(scan("f(a]"), Err(3));
+	assert_eq!(scan("f(a, { b }"), Err(1));
+	assert_eq!(scan("a /* b"), Err(2));
+}
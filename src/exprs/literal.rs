@@ -52,11 +52,16 @@ impl ExprLitTryNewErr {
 	#[inline]
 	pub fn into_tt_err(self, span: Span) -> TokenStream2 {
 		match self {
-			Self::ExpLen { current, exp } => throw_sg_err! {
-				[span]: "More char expected, current: ", #current, "exp: {}", #exp, "."
-			},
+			Self::ExpLen { current, exp } => {
+				let current = current.to_string();
+				let exp = exp.to_string();
+
+				throw_sg_err! {
+					[span]: "The literal is too short, at least ", #exp, " characters were expected, found ", #current, "."
+				}
+			}
 			Self::ExpQuotes => throw_sg_err! {
-				[span]: "Double quotes were expected."
+				[span]: "A string literal in double quotes was expected, e.g. `\"a.tt\"`."
 			},
 		}
 	}
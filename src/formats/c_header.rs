@@ -0,0 +1,934 @@
+use alloc::{
+	collections::BTreeMap,
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::cmp::Ordering;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+
+/// Value of a constant from a C header.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CValue {
+	/// Integer of the C type: its sign and width in bits (`32`, `64`).
+	Int {
+		value: i128,
+		unsigned: bool,
+		bits: u32,
+	},
+	/// `1.5`, `1.5f` (`single`).
+	Float { value: f64, single: bool },
+	/// `'a'`
+	Char(u8),
+	/// `"a" "b"`
+	Str(Vec<u8>),
+}
+
+/// A constant found in a C header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CConst {
+	pub line: usize,
+	pub name: String,
+	pub value: CValue,
+}
+
+/// A definition that cannot be translated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CSkipped {
+	pub line: usize,
+	pub name: String,
+	pub reason: String,
+}
+
+/// Constants of the header, in order of definition.
+#[derive(Debug, Default)]
+pub struct CHeader {
+	pub consts: Vec<CConst>,
+	pub skipped: Vec<CSkipped>,
+}
+
+/// Type of a C integer: (unsigned, bits).
+type CType = (bool, u32);
+
+/// `int`, the type of `'a'`, `!a` and enum constants.
+const INT: CType = (false, 32);
+
+/// Wrap the value to the type, as the conversions of C do.
+///
+/// (The overflow of signed types also wraps, as GCC and Clang do in practice)
+const fn wrap(value: i128, (unsigned, bits): CType) -> i128 {
+	let value = value & ((1 << bits) - 1);
+	match !unsigned && value >> (bits - 1) != 0 {
+		true => value - (1 << bits),
+		false => value,
+	}
+}
+
+/// Whether the value fits the type without wrapping.
+const fn fits(value: i128, ty: CType) -> bool {
+	wrap(value, ty) == value
+}
+
+/// The common type of two operands (the usual arithmetic conversions):
+/// the wider one, unsigned if the widths are equal and one of them is unsigned.
+fn common_type(a: CType, b: CType) -> CType {
+	match a.1.cmp(&b.1) {
+		Ordering::Greater => a,
+		Ordering::Less => b,
+		Ordering::Equal => (a.0 || b.0, a.1),
+	}
+}
+
+/// Trees of the C expression.
+#[derive(Debug, Clone, PartialEq)]
+enum CTok {
+	Ident(String),
+	Value(CValue),
+	Punct(&'static str),
+}
+
+/// Operators of C, the longest ones first.
+const PUNCTS: &[&str] = &[
+	"<<", ">>", "==", "!=", "<=", ">=", "&&", "||", "->", "++", "--", "+", "-", "*", "/", "%", "<",
+	">", "=", "!", "~", "&", "|", "^", "(", ")", "{", "}", "[", "]", ",", ";", ".", "?", ":", "#",
+];
+
+/// Remove comments and join lines ending with `\`, leaving literals untouched.
+///
+/// (Line breaks are kept so that line numbers do not change)
+fn clean(data: &str) -> String {
+	let mut result = String::with_capacity(data.len());
+	// Line breaks of joined lines, moved to the end of the logical line.
+	let mut pending_lines = 0;
+
+	let mut iter = data.chars().peekable();
+	while let Some(c) = iter.next() {
+		match c {
+			'"' | '\'' => {
+				result.push(c);
+				while let Some(a) = iter.next() {
+					result.push(a);
+					match a {
+						'\\' => {
+							if let Some(a) = iter.next() {
+								result.push(a);
+							}
+						}
+						'\n' => break,
+						a if a == c => break,
+						_ => {}
+					}
+				}
+			}
+			'/' if iter.peek() == Some(&'/') => while iter.next_if(|a| *a != '\n').is_some() {},
+			'/' if iter.peek() == Some(&'*') => {
+				iter.next();
+				let mut prev = ' ';
+				for a in iter.by_ref() {
+					if a == '\n' {
+						pending_lines += 1;
+					}
+					if prev == '*' && a == '/' {
+						break;
+					}
+					prev = a;
+				}
+				result.push(' ');
+			}
+			'\\' if matches!(iter.peek(), Some('\n' | '\r')) => {
+				iter.next_if(|a| *a == '\r');
+				iter.next_if(|a| *a == '\n');
+				pending_lines += 1;
+			}
+			'\n' => {
+				result.push('\n');
+				for _ in 0..pending_lines {
+					result.push('\n');
+				}
+				pending_lines = 0;
+			}
+			c => result.push(c),
+		}
+	}
+
+	result
+}
+
+/// Parse the escape sequence after `\`, `i` points to the character after `\`.
+fn parse_escape(chars: &[char], i: &mut usize) -> Result<u8, String> {
+	let c = chars.get(*i).copied();
+	*i += 1;
+
+	Ok(match c {
+		Some('n') => b'\n',
+		Some('t') => b'\t',
+		Some('r') => b'\r',
+		Some('a') => 0x07,
+		Some('b') => 0x08,
+		Some('f') => 0x0C,
+		Some('v') => 0x0B,
+		Some(c @ ('\\' | '\'' | '"' | '?')) => c as u8,
+		Some('x') => {
+			let len = chars[*i..]
+				.iter()
+				.take_while(|a| a.is_ascii_hexdigit())
+				.count();
+			let digits: String = chars[*i..*i + len].iter().collect();
+			*i += len;
+
+			match u8::from_str_radix(&digits, 16) {
+				Ok(a) => a,
+				Err(..) => return Err("invalid `\\x` escape".into()),
+			}
+		}
+		Some('0'..='7') => {
+			let len = chars[*i - 1..]
+				.iter()
+				.take(3)
+				.take_while(|a| matches!(a, '0'..='7'))
+				.count();
+			let digits: String = chars[*i - 1..*i - 1 + len].iter().collect();
+			*i += len - 1;
+
+			match u8::from_str_radix(&digits, 8) {
+				Ok(a) => a,
+				Err(..) => return Err("invalid octal escape".into()),
+			}
+		}
+		Some(c) => return Err(format!("unknown escape `\\{c}`")),
+		None => return Err("unfinished escape".into()),
+	})
+}
+
+/// Parse the number: `10`, `0x1F`, `010`, `0b1`, `10UL`, `1.5f`.
+fn parse_number(a: &str, long: u32) -> Result<CValue, String> {
+	let lower = a.to_ascii_lowercase();
+	let is_hex = lower.starts_with("0x");
+	let is_float = !is_hex && lower.contains(['.', 'e']) || is_hex && lower.contains('p');
+	if is_float {
+		if is_hex {
+			return Err(format!("hexadecimal float `{a}` is not supported"));
+		}
+		let (digits, single) = match lower.strip_suffix('f') {
+			Some(a) => (a, true),
+			None => (lower.strip_suffix('l').unwrap_or(&lower), false),
+		};
+
+		return match digits.parse::<f64>() {
+			Ok(value) if value.is_finite() => Ok(CValue::Float { value, single }),
+			_ => Err(format!("invalid float `{a}`")),
+		};
+	}
+
+	let digits = lower.trim_end_matches(['u', 'l']);
+	let suffix = &lower[digits.len()..];
+	let unsigned = suffix.contains('u');
+	let widths: &[u32] = match (suffix.contains("ll"), suffix.contains('l')) {
+		(true, _) => &[64],
+		(false, true) => &[long, 64],
+		(false, false) => &[32, long, 64],
+	};
+	let digits = digits.replace('\'', "");
+	let (radix, digits) = if let Some(a) = digits.strip_prefix("0x") {
+		(16, a)
+	} else if let Some(a) = digits.strip_prefix("0b") {
+		(2, a)
+	} else if digits.len() > 1 && digits.starts_with('0') {
+		(8, &digits[1..])
+	} else {
+		(10, digits.as_str())
+	};
+
+	let value = match u64::from_str_radix(digits, radix) {
+		Ok(a) => a as i128,
+		Err(..) => return Err(format!("invalid integer `{a}`")),
+	};
+	// The first type that fits the value: `int`, `long`, `long long`, hexadecimal
+	// and octal constants may be unsigned (`int`, `unsigned int`, `long`, ..).
+	let (unsigned, bits) = widths
+		.iter()
+		.flat_map(|&bits| [(false, bits), (true, bits)])
+		.filter(|(is_unsigned, _)| match is_unsigned {
+			true => unsigned || radix != 10,
+			false => !unsigned,
+		})
+		.find(|ty| fits(value, *ty))
+		// Too large decimal constants are unsigned, as in GCC.
+		.unwrap_or((true, 64));
+
+	Ok(CValue::Int {
+		value,
+		unsigned,
+		bits,
+	})
+}
+
+/// Split the text into C trees, `line` is the line of the first character.
+fn lex(data: &str, mut line: usize, long: u32) -> Result<Vec<(usize, CTok)>, String> {
+	let mut result = Vec::new();
+
+	let chars: Vec<char> = data.chars().collect();
+	let is_ident = |a: &char| a.is_ascii_alphanumeric() || *a == '_';
+	let mut i = 0;
+	while let Some(&c) = chars.get(i) {
+		let next = chars.get(i + 1).copied();
+		match c {
+			'\n' => {
+				i += 1;
+				line += 1;
+			}
+			c if c.is_whitespace() => i += 1,
+			c if c.is_ascii_alphabetic() || c == '_' => {
+				let len = chars[i..].iter().take_while(|a| is_ident(a)).count();
+				let ident: String = chars[i..i + len].iter().collect();
+				i += len;
+
+				if matches!(chars.get(i), Some('\'' | '"'))
+					&& matches!(&*ident, "L" | "u" | "U" | "u8")
+				{
+					return Err(format!(
+						"literals with the `{ident}` prefix are not supported"
+					));
+				}
+				result.push((line, CTok::Ident(ident)));
+			}
+			c if c.is_ascii_digit() || c == '.' && next.is_some_and(|a| a.is_ascii_digit()) => {
+				let mut number = String::new();
+				while let Some(&a) = chars.get(i) {
+					if !is_ident(&a) && a != '.' && a != '\'' {
+						break;
+					}
+					number.push(a);
+					i += 1;
+
+					// The sign of the exponent: `1e-5`, `0x1p+3`.
+					let is_hex = number.starts_with("0x") || number.starts_with("0X");
+					let is_exp = matches!(a, 'p' | 'P') || !is_hex && matches!(a, 'e' | 'E');
+					if let Some(&sign @ ('+' | '-')) = chars.get(i).filter(|_| is_exp) {
+						number.push(sign);
+						i += 1;
+					}
+				}
+				result.push((line, CTok::Value(parse_number(&number, long)?)));
+			}
+			'\'' => {
+				i += 1;
+				let value = match chars.get(i) {
+					Some('\\') => {
+						i += 1;
+						parse_escape(&chars, &mut i)?
+					}
+					Some(a) if a.is_ascii() && *a != '\'' => {
+						i += 1;
+						*a as u8
+					}
+					_ => return Err("unsupported character literal".into()),
+				};
+				if chars.get(i) != Some(&'\'') {
+					return Err("multi-character literals are not supported".into());
+				}
+				i += 1;
+				result.push((line, CTok::Value(CValue::Char(value))));
+			}
+			'"' => {
+				i += 1;
+				let mut value = Vec::new();
+				loop {
+					let a = chars.get(i).copied();
+					i += 1;
+					match a {
+						Some('"') => break,
+						Some('\\') => value.push(parse_escape(&chars, &mut i)?),
+						Some('\n') | None => return Err("unclosed string".into()),
+						Some(a) => {
+							let mut buf = [0; 4];
+							value.extend_from_slice(a.encode_utf8(&mut buf).as_bytes());
+						}
+					}
+				}
+				result.push((line, CTok::Value(CValue::Str(value))));
+			}
+			_ => {
+				let rest: String = chars[i..].iter().take(2).collect();
+				match PUNCTS.iter().find(|a| rest.starts_with(**a)) {
+					Some(punct) => {
+						i += punct.len();
+						result.push((line, CTok::Punct(punct)));
+					}
+					None => return Err(format!("unexpected character `{c}`")),
+				}
+			}
+		}
+	}
+
+	Ok(result)
+}
+
+/// Calculation of C expressions over the already known constants.
+struct Eval<'a> {
+	toks: &'a [(usize, CTok)],
+	pos: usize,
+	env: &'a BTreeMap<String, CValue>,
+}
+
+/// Integer operand of the expression: (value, type).
+type CInt = (i128, CType);
+
+impl Eval<'_> {
+	fn peek(&self) -> Option<&CTok> {
+		self.toks.get(self.pos).map(|(_, a)| a)
+	}
+
+	fn next_if_punct(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+		match self.peek() {
+			Some(CTok::Punct(p)) if ops.contains(p) => {
+				let p = *p;
+				self.pos += 1;
+				Some(p)
+			}
+			_ => None,
+		}
+	}
+
+	/// The whole expression, only integers are computed.
+	fn expr(&mut self) -> Result<CValue, String> {
+		let value = match self.toks {
+			// `"a" "b"`
+			[(_, CTok::Value(CValue::Str(..))), ..] => {
+				let mut result = Vec::new();
+				for (_, tok) in self.toks {
+					match tok {
+						CTok::Value(CValue::Str(a)) => result.extend_from_slice(a),
+						_ => return Err("only strings can follow a string".into()),
+					}
+				}
+				self.pos = self.toks.len();
+				CValue::Str(result)
+			}
+			[(_, CTok::Value(a @ (CValue::Char(..) | CValue::Float { .. })))] => {
+				self.pos = 1;
+				a.clone()
+			}
+			[
+				(_, CTok::Punct("-")),
+				(_, CTok::Value(CValue::Float { value, single })),
+			] => {
+				self.pos = 2;
+				CValue::Float {
+					value: -value,
+					single: *single,
+				}
+			}
+			_ => {
+				let (value, (unsigned, bits)) = self.binary(0)?;
+				CValue::Int {
+					value,
+					unsigned,
+					bits,
+				}
+			}
+		};
+
+		match self.toks.get(self.pos) {
+			None => Ok(value),
+			Some((_, tok)) => Err(format!("unexpected `{}`", describe(tok))),
+		}
+	}
+
+	fn binary(&mut self, level: usize) -> Result<CInt, String> {
+		const LEVELS: &[&[&str]] = &[
+			&["|"],
+			&["^"],
+			&["&"],
+			&["<<", ">>"],
+			&["+", "-"],
+			&["*", "/", "%"],
+		];
+		let Some(ops) = LEVELS.get(level) else {
+			return self.unary();
+		};
+
+		let mut left = self.binary(level + 1)?;
+		while let Some(op) = self.next_if_punct(ops) {
+			let right = self.binary(level + 1)?;
+			// The result has the common type of the operands,
+			// a shift has the type of the left one.
+			let ty = match op {
+				"<<" | ">>" => left.1,
+				_ => common_type(left.1, right.1),
+			};
+			let (a, b) = (wrap(left.0, ty), wrap(right.0, ty));
+			let value = match op {
+				"<<" | ">>" if !(0..ty.1 as i128).contains(&right.0) => {
+					return Err(format!(
+						"`{op}` by {} is out of range of the {}-bit type",
+						right.0, ty.1
+					));
+				}
+				"<<" => Some(a.wrapping_shl(right.0 as u32)),
+				">>" => Some(a >> right.0),
+				"|" => Some(a | b),
+				"^" => Some(a ^ b),
+				"&" => Some(a & b),
+				"+" => Some(a.wrapping_add(b)),
+				"-" => Some(a.wrapping_sub(b)),
+				"*" => Some(a.wrapping_mul(b)),
+				"/" => a.checked_div(b),
+				_ => a.checked_rem(b),
+			};
+			left = match value {
+				Some(value) => (wrap(value, ty), ty),
+				None => return Err(format!("`{op}` divides by zero")),
+			};
+		}
+
+		Ok(left)
+	}
+
+	fn unary(&mut self) -> Result<CInt, String> {
+		match self.next_if_punct(&["-", "+", "~", "!"]) {
+			Some(op) => {
+				let (value, ty) = self.unary()?;
+				Ok(match op {
+					"-" => (wrap(-value, ty), ty),
+					"+" => (value, ty),
+					"~" => (wrap(!value, ty), ty),
+					_ => ((value == 0) as i128, INT),
+				})
+			}
+			None => self.primary(),
+		}
+	}
+
+	fn primary(&mut self) -> Result<CInt, String> {
+		let tok = match self.toks.get(self.pos) {
+			Some((_, tok)) => tok,
+			None => return Err("unexpected end of the expression".into()),
+		};
+		self.pos += 1;
+
+		match tok {
+			CTok::Value(CValue::Int {
+				value,
+				unsigned,
+				bits,
+			}) => Ok((*value, (*unsigned, *bits))),
+			CTok::Value(CValue::Char(a)) => Ok((*a as i128, INT)),
+			CTok::Value(..) => Err("strings and floats cannot be used in expressions".into()),
+			CTok::Ident(name) => match self.env.get(name) {
+				Some(CValue::Int {
+					value,
+					unsigned,
+					bits,
+				}) => Ok((*value, (*unsigned, *bits))),
+				Some(CValue::Char(a)) => Ok((*a as i128, INT)),
+				Some(..) => Err(format!("`{name}` is not an integer")),
+				None => Err(format!("unknown identifier `{name}`")),
+			},
+			CTok::Punct("(") => {
+				if matches!(self.peek(), Some(CTok::Ident(name)) if !self.env.contains_key(name)) {
+					return Err("casts are not supported".into());
+				}
+				let value = self.binary(0)?;
+				match self.next_if_punct(&[")"]) {
+					Some(..) => Ok(value),
+					None => Err("`)` was expected".into()),
+				}
+			}
+			tok => Err(format!("unexpected `{}`", describe(tok))),
+		}
+	}
+}
+
+/// Text of the tree for error messages.
+fn describe(tok: &CTok) -> String {
+	match tok {
+		CTok::Ident(a) => a.clone(),
+		CTok::Punct(a) => (*a).into(),
+		CTok::Value(a) => format!("{a:?}"),
+	}
+}
+
+impl CHeader {
+	/// Remember the constant, or skip it if the name is already defined.
+	fn push(&mut self, env: &mut BTreeMap<String, CValue>, line: usize, name: &str, value: CValue) {
+		if env.contains_key(name) {
+			return self.skip(line, name, "the name is already defined".into());
+		}
+
+		env.insert(name.into(), value.clone());
+		self.consts.push(CConst {
+			line,
+			name: name.into(),
+			value,
+		});
+	}
+
+	fn skip(&mut self, line: usize, name: &str, reason: String) {
+		self.skipped.push(CSkipped {
+			line,
+			name: name.into(),
+			reason,
+		});
+	}
+
+	/// Scan the `enum { A, B = 5, C }` constants in the trees.
+	fn scan_enums(&mut self, env: &mut BTreeMap<String, CValue>, toks: &[(usize, CTok)]) {
+		let mut i = 0;
+		while i < toks.len() {
+			let start = match &toks[i..] {
+				[(_, CTok::Ident(e)), (_, CTok::Punct("{")), ..] if e == "enum" => i + 2,
+				[
+					(_, CTok::Ident(e)),
+					(_, CTok::Ident(..)),
+					(_, CTok::Punct("{")),
+					..,
+				] if e == "enum" => i + 3,
+				_ => {
+					i += 1;
+					continue;
+				}
+			};
+			let end = match toks[start..]
+				.iter()
+				.position(|(_, a)| *a == CTok::Punct("}"))
+			{
+				Some(a) => start + a,
+				None => return,
+			};
+
+			// The value of the next implicit constant.
+			let mut next = Some(0i128);
+			for entry in toks[start..end].split(|(_, a)| *a == CTok::Punct(",")) {
+				let (line, name, expr) = match entry {
+					[] => continue,
+					[(line, CTok::Ident(name))] => (*line, name, None),
+					[(line, CTok::Ident(name)), (_, CTok::Punct("=")), expr @ ..] => {
+						(*line, name, Some(expr))
+					}
+					[(line, tok), ..] => {
+						self.skip(*line, &describe(tok), "unexpected enum entry".into());
+						next = None;
+						continue;
+					}
+				};
+
+				let value = match expr {
+					Some(expr) => Eval {
+						toks: expr,
+						pos: 0,
+						env: &*env,
+					}
+					.expr()
+					.and_then(|a| match a {
+						CValue::Int { value, .. } => Ok(value),
+						CValue::Char(a) => Ok(a as i128),
+						_ => Err("an enum constant must be an integer".into()),
+					}),
+					None => next.ok_or_else(|| {
+						String::from("the value of the previous constant is unknown")
+					}),
+				};
+				// The constants are `int`, the larger ones take a wider type (as in GCC).
+				let value = value.and_then(|value| {
+					match [INT, (true, 32), (false, 64), (true, 64)]
+						.into_iter()
+						.find(|ty| fits(value, *ty))
+					{
+						Some(ty) => Ok((value, ty)),
+						None => Err(format!("`{value}` does not fit in 64 bits")),
+					}
+				});
+				match value {
+					Ok((value, (unsigned, bits))) => {
+						next = value.checked_add(1);
+						self.push(
+							env,
+							line,
+							name,
+							CValue::Int {
+								value,
+								unsigned,
+								bits,
+							},
+						);
+					}
+					Err(reason) => {
+						next = None;
+						self.skip(line, name, reason);
+					}
+				}
+			}
+			i = end + 1;
+		}
+	}
+}
+
+/// Find the `#define NAME value` and `enum { ... }` constants in the C header.
+///
+/// Flags without a value (`#define HEADER_H`) and other directives are ignored,
+/// both branches of `#if` are read. `long` is the width of `long` in bits
+/// on the target: `64`, or `32` on Windows and 32-bit targets.
+pub fn parse_c_header(data: &str, long: u32) -> CHeader {
+	let mut header = CHeader::default();
+	let mut env = BTreeMap::new();
+
+	let data = clean(data);
+	// Text after the last directive, for the search of enums.
+	let mut code = String::with_capacity(data.len());
+	for (i, line) in data.split('\n').enumerate() {
+		let line_num = i + 1;
+		let Some(directive) = line.trim_start().strip_prefix('#') else {
+			code.push_str(line);
+			code.push('\n');
+			continue;
+		};
+		// The enums before the directive may be used in its value.
+		if let Ok(toks) = lex(&code, line_num - code.matches('\n').count(), long) {
+			header.scan_enums(&mut env, &toks);
+		}
+		code.clear();
+		code.push('\n');
+
+		let Some(define) = directive.trim_start().strip_prefix("define") else {
+			continue;
+		};
+		if !define.starts_with(char::is_whitespace) {
+			continue;
+		}
+		let define = define.trim_start();
+		let name_len = define
+			.find(|a: char| !a.is_ascii_alphanumeric() && a != '_')
+			.unwrap_or(define.len());
+		let (name, value) = define.split_at(name_len);
+		if name.is_empty() {
+			continue;
+		}
+		if value.starts_with('(') {
+			header.skip(
+				line_num,
+				name,
+				"function-like macros are not supported".into(),
+			);
+			continue;
+		}
+		if value.trim().is_empty() {
+			continue;
+		}
+
+		let result = lex(value, line_num, long).and_then(|toks| {
+			Eval {
+				toks: &toks,
+				pos: 0,
+				env: &env,
+			}
+			.expr()
+		});
+		match result {
+			Ok(value) => header.push(&mut env, line_num, name, value),
+			Err(reason) => header.skip(line_num, name, reason),
+		}
+	}
+	if let Ok(toks) = lex(
+		&code,
+		data.split('\n').count() + 1 - code.matches('\n').count(),
+		long,
+	) {
+		header.scan_enums(&mut env, &toks);
+	}
+
+	header
+}
+
+impl CValue {
+	/// Get the Rust type and value of the constant.
+	///
+	/// Integers keep the type of C: `int` is `i32`, `unsigned int` is `u32`,
+	/// `long long` is `i64`, `long` has the width given to `parse_c_header`.
+	pub fn to_tokens(&self, span: Span) -> Result<(TokenStream2, TokenStream2), String> {
+		let (ty, lit, is_neg) = match self {
+			Self::Int {
+				value,
+				unsigned,
+				bits,
+			} => {
+				let ty = match (unsigned, bits) {
+					(false, 32) => "i32",
+					(true, 32) => "u32",
+					(false, 64) => "i64",
+					(true, 64) => "u64",
+					_ => return Err(format!("{bits}-bit integers are not supported")),
+				};
+				let lit = match u64::try_from(value.unsigned_abs()) {
+					Ok(a) => Literal::u64_unsuffixed(a),
+					Err(..) => return Err(format!("`{value}` does not fit in 64 bits")),
+				};
+				(ty, lit, *value < 0)
+			}
+			Self::Float {
+				value,
+				single: true,
+			} => (
+				"f32",
+				Literal::f32_unsuffixed(value.abs() as f32),
+				*value < 0.0,
+			),
+			Self::Float { value, .. } => {
+				("f64", Literal::f64_unsuffixed(value.abs()), *value < 0.0)
+			}
+			Self::Char(a) => ("u8", Literal::byte_character(*a), false),
+			Self::Str(a) => match core::str::from_utf8(a) {
+				Ok(a) => ("&str", Literal::string(a), false),
+				Err(..) => ("&[u8]", Literal::byte_string(a), false),
+			},
+		};
+
+		let mut lit = lit;
+		lit.set_span(span);
+		let ty: TokenStream2 = match ty.parse() {
+			Ok(a) => a,
+			Err(e) => return Err(e.to_string()),
+		};
+		let value = match is_neg {
+			true => quote_spanned! { span => -#lit },
+			false => quote_spanned! { span => #lit },
+		};
+
+		Ok((quote_spanned! { span => #ty }, value))
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_c_header() {
+	let header = parse_c_header(
+		"#ifndef REGS_H\n\
+		#define REGS_H\n\
+		/* base\n address */\n\
+		#define BASE 0x4000UL // comment\n\
+		#define CTRL (BASE + 0x10)\n\
+		#define MASK \\\n\t(~0U)\n\
+		#define NAME \"uart\" \"0\"\n\
+		#define SEP ','\n\
+		#define NEG -5\n\
+		#define F(x) ((x) + 1)\n\
+		#define CAST ((uint32_t)1)\n\
+		enum mode { OFF, ON = CTRL - BASE, AUTO };\n\
+		#define AFTER (AUTO << 1)\n\
+		#endif\n",
+		64,
+	);
+	let consts: Vec<(usize, &str, &CValue)> = header
+		.consts
+		.iter()
+		.map(|a| (a.line, &*a.name, &a.value))
+		.collect();
+	let int = |value, unsigned, bits| CValue::Int {
+		value,
+		unsigned,
+		bits,
+	};
+
+	assert_eq!(
+		consts,
+		[
+			(5, "BASE", &int(0x4000, true, 64)),
+			(6, "CTRL", &int(0x4010, true, 64)),
+			(7, "MASK", &int(0xFFFF_FFFF, true, 32)),
+			(9, "NAME", &CValue::Str(b"uart0".to_vec())),
+			(10, "SEP", &CValue::Char(b',')),
+			(11, "NEG", &int(-5, false, 32)),
+			(14, "OFF", &int(0, false, 32)),
+			(14, "ON", &int(0x10, false, 32)),
+			(14, "AUTO", &int(0x11, false, 32)),
+			(15, "AFTER", &int(0x22, false, 32)),
+		]
+	);
+	let skipped: Vec<(usize, &str)> = header.skipped.iter().map(|a| (a.line, &*a.name)).collect();
+	assert_eq!(skipped, [(12, "F"), (13, "CAST")]);
+
+	let span = Span::call_site();
+	let (ty, value) = int(0xFFFF_FFFF, true, 32).to_tokens(span).unwrap();
+	assert_eq!(
+		(ty.to_string(), value.to_string()),
+		("u32".into(), "4294967295".into())
+	);
+	let ty = |a: &str| match parse_number(a, 64).unwrap().to_tokens(span) {
+		Ok((ty, _)) => ty.to_string(),
+		Err(e) => e,
+	};
+	assert_eq!(ty("0x7FFFFFFF"), "i32");
+	assert_eq!(ty("0x80000000"), "u32");
+	assert_eq!(ty("020000000000"), "u32");
+	assert_eq!(ty("0x100000000"), "i64");
+	assert_eq!(ty("0x8000000000000000"), "u64");
+	assert_eq!(ty("0x80000000L"), "i64");
+	assert_eq!(ty("2147483648"), "i64");
+	let (ty, value) = int(-5, false, 32).to_tokens(span).unwrap();
+	assert_eq!(
+		(ty.to_string(), value.to_string()),
+		("i32".into(), "- 5".into())
+	);
+}
+
+#[cfg(test)]
+#[test]
+fn test_c_header_int_types() {
+	let eval = |a: &str, long| {
+		lex(a, 1, long).and_then(|toks| {
+			Eval {
+				toks: &toks,
+				pos: 0,
+				env: &BTreeMap::new(),
+			}
+			.expr()
+		})
+	};
+	let int = |value, unsigned, bits| {
+		Ok(CValue::Int {
+			value,
+			unsigned,
+			bits,
+		})
+	};
+
+	// The width of `long` depends on the target.
+	assert_eq!(eval("0x40001000UL", 32), int(0x4000_1000, true, 32));
+	assert_eq!(eval("0x40001000UL", 64), int(0x4000_1000, true, 64));
+	assert_eq!(eval("0x80000000L", 32), int(0x8000_0000, true, 32));
+	assert_eq!(eval("2147483648L", 32), int(2_147_483_648, false, 64));
+	assert_eq!(eval("1LL", 32), int(1, false, 64));
+
+	// The result wraps to the common type of the operands.
+	assert_eq!(eval("(0xFFFFFFFFU + 1)", 64), int(0, true, 32));
+	assert_eq!(eval("(0xFFFFFFFFUL + 1)", 64), int(0x1_0000_0000, true, 64));
+	assert_eq!(eval("(-1 + 0U)", 64), int(0xFFFF_FFFF, true, 32));
+	assert_eq!(eval("(0U - 1UL)", 32), int(0xFFFF_FFFF, true, 32));
+	assert_eq!(eval("(-1 + 0UL)", 64), int(u64::MAX as i128, true, 64));
+	assert_eq!(
+		eval("(0x7FFFFFFF + 1)", 64),
+		int(i32::MIN as i128, false, 32)
+	);
+	assert_eq!(eval("(0x8000000000000000 * 2)", 64), int(0, true, 64));
+	assert_eq!(eval("-0x80000000", 64), int(0x8000_0000, true, 32));
+	assert_eq!(eval("~0ULL", 64), int(u64::MAX as i128, true, 64));
+
+	// A shift has the type of the left operand.
+	assert_eq!(eval("(1 << 31)", 64), int(i32::MIN as i128, false, 32));
+	assert_eq!(eval("(1U << 31)", 64), int(0x8000_0000, true, 32));
+	assert_eq!(eval("(1ULL << 63)", 64), int(1 << 63, true, 64));
+	assert_eq!(eval("(1 << 4ULL)", 64), int(16, false, 32));
+	assert_eq!(eval("(-16 >> 2)", 64), int(-4, false, 32));
+	assert_eq!(eval("(0x80000000 >> 31)", 64), int(1, true, 32));
+	assert_eq!(
+		eval("(1 << 32)", 64),
+		Err("`<<` by 32 is out of range of the 32-bit type".into())
+	);
+	assert_eq!(
+		eval("(1L << 32)", 32),
+		Err("`<<` by 32 is out of range of the 32-bit type".into())
+	);
+	assert_eq!(eval("(1L << 32)", 64), int(0x1_0000_0000, false, 64));
+	assert_eq!(eval("(1 / 0)", 64), Err("`/` divides by zero".into()));
+}
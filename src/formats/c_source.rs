@@ -0,0 +1,413 @@
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+/// Construct of the C source changed by `prepare_c_source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CAltered {
+	/// `\` at the end of the line outside of literals.
+	LineContinuation,
+	/// `///`, `//!`, `/**`, `/*!` would become Rust documentation.
+	DocComment,
+	/// `/*` inside a block comment, which is nested in Rust.
+	NestedComment,
+	/// `'ab'`, replaced by its integer value.
+	MultiCharLiteral,
+	/// `'\101'`, `'\a'`, an escape that Rust does not have, replaced by the integer value.
+	CharEscape,
+	/// `#include <a.h>`, turned into a comment.
+	Directive,
+	/// `` ` ``, replaced by a space.
+	Backtick,
+	/// `\` outside of literals not ending the line, replaced by a space.
+	Backslash,
+}
+
+impl CAltered {
+	/// Description of the construct for messages.
+	pub const fn description(self) -> &'static str {
+		match self {
+			Self::LineContinuation => "line continuation",
+			Self::DocComment => "comment that looks like Rust documentation",
+			Self::NestedComment => "`/*` inside a comment",
+			Self::MultiCharLiteral => "multi-character literal",
+			Self::CharEscape => "character literal with a C escape",
+			Self::Directive => "preprocessor directive",
+			Self::Backtick => "backtick",
+			Self::Backslash => "`\\` outside of literals",
+		}
+	}
+}
+
+/// The construct changed on the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CAlteration {
+	pub line: usize,
+	pub kind: CAltered,
+}
+
+/// Describe the changes for messages: `line 3: line continuation, line 7: backtick`.
+pub fn describe_alterations(list: &[CAlteration]) -> String {
+	list.iter()
+		.map(|a| format!("line {}: {}", a.line, a.kind.description()))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// Get the value of the character literal if Rust cannot read it as is.
+///
+/// (`None` if the literal can stay unchanged)
+fn c_char_value(content: &[char]) -> Result<Option<(CAltered, u32)>, ()> {
+	let mut values = Vec::new();
+	let mut is_rust_escape = true;
+
+	let mut i = 0;
+	while let Some(&c) = content.get(i) {
+		i += 1;
+		if c != '\\' {
+			values.push(c as u32);
+			continue;
+		}
+
+		let e = content.get(i).copied().ok_or(())?;
+		i += 1;
+		values.push(match e {
+			'n' => 0x0A,
+			't' => 0x09,
+			'r' => 0x0D,
+			'\\' | '\'' | '"' => e as u32,
+			'0' if !content.get(i).is_some_and(|a| a.is_digit(8)) => 0,
+			'x' => {
+				let len = content[i..]
+					.iter()
+					.take_while(|a| a.is_ascii_hexdigit())
+					.count();
+				let digits: String = content[i..i + len].iter().collect();
+				i += len;
+				// Rust accepts only `\xHH` up to `\x7F`.
+				let value = u32::from_str_radix(&digits, 16).map_err(|_| ())?;
+				is_rust_escape &= len == 2 && value <= 0x7F;
+				value
+			}
+			'0'..='7' => {
+				let len = content[i - 1..]
+					.iter()
+					.take(3)
+					.take_while(|a| a.is_digit(8))
+					.count();
+				let digits: String = content[i - 1..i - 1 + len].iter().collect();
+				i += len - 1;
+				is_rust_escape = false;
+				u32::from_str_radix(&digits, 8).map_err(|_| ())?
+			}
+			'a' | 'b' | 'f' | 'v' | '?' | 'e' => {
+				is_rust_escape = false;
+				match e {
+					'a' => 0x07,
+					'b' => 0x08,
+					'f' => 0x0C,
+					'v' => 0x0B,
+					'e' => 0x1B,
+					_ => '?' as u32,
+				}
+			}
+			_ => return Err(()),
+		});
+	}
+
+	match values.as_slice() {
+		[] => Err(()),
+		[_] if is_rust_escape => Ok(None),
+		[value] => Ok(Some((CAltered::CharEscape, *value))),
+		// As GCC does: `'ab'` is `'a' << 8 | 'b'`.
+		values if values.len() <= 4 => Ok(Some((
+			CAltered::MultiCharLiteral,
+			values.iter().fold(0, |acc, a| acc << 8 | (a & 0xFF)),
+		))),
+		_ => Err(()),
+	}
+}
+
+/// Prepare the C source for the Rust lexer: only the constructs outside of
+/// literals that Rust cannot read are changed, line breaks are kept.
+pub fn prepare_c_source(data: &str) -> (String, Vec<CAlteration>) {
+	let chars: Vec<char> = data.chars().collect();
+	let mut result = String::with_capacity(data.len());
+	let mut altered = Vec::new();
+
+	let mut line = 1;
+	let mut is_line_start = true;
+	// `\` and the whitespace up to the end of the line (the length).
+	let continuation_len = |i: usize| -> Option<usize> {
+		let spaces = chars[i + 1..]
+			.iter()
+			.take_while(|a| matches!(a, ' ' | '\t' | '\r'))
+			.count();
+		match chars.get(i + 1 + spaces) {
+			Some('\n') => Some(1 + spaces),
+			_ => None,
+		}
+	};
+
+	let mut i = 0;
+	while let Some(&c) = chars.get(i) {
+		let next = chars.get(i + 1).copied();
+		match c {
+			'\n' => {
+				result.push('\n');
+				line += 1;
+				is_line_start = true;
+				i += 1;
+				continue;
+			}
+			c if c.is_whitespace() => {
+				result.push(c);
+				i += 1;
+				continue;
+			}
+			// `#include <a.h>`, but not `#[attr]`.
+			'#' if is_line_start
+				&& chars[i + 1..]
+					.iter()
+					.find(|a| !matches!(a, ' ' | '\t'))
+					.is_none_or(|a| a.is_ascii_alphabetic() || *a == '\n') =>
+			{
+				altered.push(CAlteration {
+					line,
+					kind: CAltered::Directive,
+				});
+				result.push_str("//");
+				while let Some(&a) = chars.get(i) {
+					match a {
+						'\\' if continuation_len(i).is_some() => {
+							let len = continuation_len(i).unwrap_or_default();
+							result.push_str("\n//");
+							line += 1;
+							i += len + 1;
+						}
+						'\n' => break,
+						a => {
+							result.push(a);
+							i += 1;
+						}
+					}
+				}
+			}
+			'"' => {
+				result.push(c);
+				i += 1;
+				while let Some(&a) = chars.get(i) {
+					i += 1;
+					result.push(a);
+					match a {
+						'\\' => {
+							if let Some(&a) = chars.get(i) {
+								i += 1;
+								result.push(a);
+								if a == '\n' {
+									line += 1;
+								}
+							}
+						}
+						'"' => break,
+						'\n' => {
+							line += 1;
+							break;
+						}
+						_ => {}
+					}
+				}
+			}
+			// `'a,`, `'a>`, `&'static str` are Rust lifetimes, not literals.
+			'\'' if next.is_some_and(|a| a.is_alphabetic() || a == '_')
+				&& chars[i + 1..]
+					.iter()
+					.find(|a| !(a.is_alphanumeric() || **a == '_'))
+					.is_none_or(|a| *a != '\'') =>
+			{
+				result.push(c);
+				i += 1;
+			}
+			'\'' => {
+				// The end of the literal on the same line.
+				let mut end = None;
+				let mut j = i + 1;
+				while let Some(&a) = chars.get(j) {
+					match a {
+						'\\' => j += 2,
+						'\'' => {
+							end = Some(j);
+							break;
+						}
+						'\n' => break,
+						_ => j += 1,
+					}
+				}
+
+				match end.map(|end| (end, c_char_value(&chars[i + 1..end]))) {
+					Some((end, Ok(Some((kind, value))))) => {
+						altered.push(CAlteration { line, kind });
+						result.push_str(&value.to_string());
+						i = end + 1;
+					}
+					Some((end, _)) => {
+						result.extend(&chars[i..=end]);
+						i = end + 1;
+					}
+					None => {
+						result.push(c);
+						i += 1;
+					}
+				}
+			}
+			'/' if next == Some('/') => {
+				result.push_str("//");
+				i += 2;
+				if matches!(chars.get(i), Some('/' | '!')) {
+					altered.push(CAlteration {
+						line,
+						kind: CAltered::DocComment,
+					});
+					result.push(' ');
+				}
+				while let Some(&a) = chars.get(i) {
+					match a {
+						// The comment continues on the next line in C.
+						'\\' if continuation_len(i).is_some() => {
+							let len = continuation_len(i).unwrap_or_default();
+							altered.push(CAlteration {
+								line,
+								kind: CAltered::LineContinuation,
+							});
+							result.push_str("\n//");
+							line += 1;
+							i += len + 1;
+						}
+						'\n' => break,
+						a => {
+							result.push(a);
+							i += 1;
+						}
+					}
+				}
+			}
+			'/' if next == Some('*') => {
+				let start = i + 2;
+				let end = chars[start..].windows(2).position(|a| a == ['*', '/']);
+				let content = match end {
+					Some(end) => &chars[start..start + end],
+					None => &chars[start..],
+				};
+
+				result.push_str("/*");
+				let is_doc = matches!(content, ['!', ..] | ['*', _, ..])
+					&& !matches!(content, ['*', '*', ..]);
+				if is_doc {
+					altered.push(CAlteration {
+						line,
+						kind: CAltered::DocComment,
+					});
+					result.push(' ');
+				}
+				let mut prev = ' ';
+				for &a in content {
+					if prev == '/' && a == '*' {
+						altered.push(CAlteration {
+							line,
+							kind: CAltered::NestedComment,
+						});
+						result.push(' ');
+					}
+					if a == '\n' {
+						line += 1;
+					}
+					result.push(a);
+					prev = a;
+				}
+				match end {
+					Some(..) => {
+						result.push_str("*/");
+						i = start + content.len() + 2;
+					}
+					None => i = chars.len(),
+				}
+			}
+			'\\' if continuation_len(i).is_some() => {
+				let len = continuation_len(i).unwrap_or_default();
+				altered.push(CAlteration {
+					line,
+					kind: CAltered::LineContinuation,
+				});
+				// The line break is kept.
+				for _ in 0..len {
+					result.push(' ');
+				}
+				i += len;
+			}
+			'`' | '\\' => {
+				altered.push(CAlteration {
+					line,
+					kind: match c {
+						'`' => CAltered::Backtick,
+						_ => CAltered::Backslash,
+					},
+				});
+				result.push(' ');
+				i += 1;
+			}
+			c => {
+				result.push(c);
+				i += 1;
+			}
+		}
+		is_line_start = false;
+	}
+
+	(result, altered)
+}
+
+#[cfg(test)]
+#[test]
+fn test_prepare_c_source() {
+	let (result, altered) = prepare_c_source(
+		"#define A(x) \\\n\t(x + 1)\n\
+		int a = 1 + \\  \n2; // a \\\ncomment\n\
+		char *s = \"a\\\nb // not a comment\";\n\
+		/// doc\n\
+		/* a /* b */ int c = 'ab' + '\\101' + 'x' + '\\n';\n\
+		`x` \\ y\n\
+		fn f<'a, 'b>(x: &'a str, y: &'b [u8]) -> &'static str { x }\n",
+	);
+	assert_eq!(
+		result,
+		"//#define A(x) \n//\t(x + 1)\n\
+		int a = 1 +    \n2; // a \n//comment\n\
+		char *s = \"a\\\nb // not a comment\";\n\
+		// / doc\n\
+		/* a / * b */ int c = 24930 + 65 + 'x' + '\\n';\n \
+		x    y\n\
+		fn f<'a, 'b>(x: &'a str, y: &'b [u8]) -> &'static str { x }\n"
+	);
+	let kinds: Vec<(usize, CAltered)> = altered.iter().map(|a| (a.line, a.kind)).collect();
+	assert_eq!(
+		kinds,
+		[
+			(1, CAltered::Directive),
+			(3, CAltered::LineContinuation),
+			(4, CAltered::LineContinuation),
+			(8, CAltered::DocComment),
+			(9, CAltered::NestedComment),
+			(9, CAltered::MultiCharLiteral),
+			(9, CAltered::CharEscape),
+			(10, CAltered::Backtick),
+			(10, CAltered::Backtick),
+			(10, CAltered::Backslash),
+		]
+	);
+	assert_eq!(
+		describe_alterations(&altered[..2]),
+		"line 1: preprocessor directive, line 3: line continuation"
+	);
+}
@@ -0,0 +1,98 @@
+use alloc::{string::String, vec::Vec};
+
+/// Supported cases of identifiers and strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+	/// `snake_case`
+	Snake,
+	/// `camelCase`
+	Camel,
+	/// `PascalCase`
+	Pascal,
+	/// `SHOUTY_SNAKE_CASE`
+	Shouty,
+}
+
+/// Split a string into words by separators (anything except letters and digits)
+/// and by case boundaries: `HTTPServerV2` -> `HTTP`, `Server`, `V2`.
+pub fn split_words(a: &str) -> Vec<&str> {
+	let mut words = Vec::new();
+	let chars: Vec<(usize, char)> = a.char_indices().collect();
+
+	let mut start = None;
+	for (i, &(pos, c)) in chars.iter().enumerate() {
+		if !c.is_alphanumeric() {
+			if let Some(start) = start.take() {
+				words.push(&a[start..pos]);
+			}
+			continue;
+		}
+
+		if let Some(wstart) = start {
+			let prev = chars[i - 1].1;
+			let next = chars.get(i + 1).map(|a| a.1);
+
+			let is_boundary = c.is_uppercase()
+				&& (prev.is_lowercase()
+					|| prev.is_numeric()
+					|| (prev.is_uppercase() && next.is_some_and(|a| a.is_lowercase())));
+			if is_boundary {
+				words.push(&a[wstart..pos]);
+				start = Some(pos);
+			}
+		} else {
+			start = Some(pos);
+		}
+	}
+	if let Some(start) = start {
+		words.push(&a[start..]);
+	}
+
+	words
+}
+
+impl Case {
+	/// Convert the string to the case, the `r#` prefix is kept as is.
+	pub fn convert(self, a: &str) -> String {
+		let (prefix, a) = match a.strip_prefix("r#") {
+			Some(a) => ("r#", a),
+			None => ("", a),
+		};
+
+		let mut result = String::from(prefix);
+		for (i, word) in split_words(a).into_iter().enumerate() {
+			match self {
+				Self::Snake | Self::Shouty if i != 0 => result.push('_'),
+				_ => {}
+			}
+
+			let mut chars = word.chars();
+			match self {
+				Self::Snake => result.extend(chars.flat_map(char::to_lowercase)),
+				Self::Shouty => result.extend(chars.flat_map(char::to_uppercase)),
+				Self::Camel if i == 0 => result.extend(chars.flat_map(char::to_lowercase)),
+				Self::Camel | Self::Pascal => {
+					if let Some(first) = chars.next() {
+						result.extend(first.to_uppercase());
+					}
+					result.extend(chars.flat_map(char::to_lowercase));
+				}
+			}
+		}
+
+		result
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_case() {
+	assert_eq!(split_words("HTTPServerV2"), ["HTTP", "Server", "V2"]);
+	assert_eq!(split_words("__my-var name_"), ["my", "var", "name"]);
+
+	assert_eq!(Case::Snake.convert("MyEnumVariant"), "my_enum_variant");
+	assert_eq!(Case::Shouty.convert("myEnumVariant"), "MY_ENUM_VARIANT");
+	assert_eq!(Case::Camel.convert("my_enum_variant"), "myEnumVariant");
+	assert_eq!(Case::Pascal.convert("my-enum variant"), "MyEnumVariant");
+	assert_eq!(Case::Pascal.convert("r#type"), "r#Type");
+}
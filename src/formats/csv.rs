@@ -0,0 +1,99 @@
+use alloc::{format, string::String, vec::Vec};
+
+/// One record of the file and the number of the line on which it begins.
+pub struct CsvRecord {
+	pub line: usize,
+	pub fields: Vec<String>,
+}
+
+/// Parse the CSV text (RFC 4180): fields are separated by `,`, records by a line break,
+/// a field in double quotes can contain `,`, line breaks and `""` (one quote).
+///
+/// Empty lines are skipped.
+pub fn parse_csv(data: &str) -> Result<Vec<CsvRecord>, String> {
+	let mut records = Vec::new();
+
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut is_empty_record = true;
+	let mut line = 1;
+	let mut record_line = 1;
+
+	let mut chars = data.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if field.is_empty() => {
+				let quote_line = line;
+				is_empty_record = false;
+				loop {
+					match chars.next() {
+						Some('"') if chars.peek() == Some(&'"') => {
+							chars.next();
+							field.push('"');
+						}
+						Some('"') => break,
+						Some(c) => {
+							if c == '\n' {
+								line += 1;
+							}
+							field.push(c);
+						}
+						None => {
+							return Err(format!(
+								"the quoted field on line {quote_line} is not closed"
+							));
+						}
+					}
+				}
+			}
+			',' => {
+				is_empty_record = false;
+				fields.push(core::mem::take(&mut field));
+			}
+			'\r' if chars.peek() == Some(&'\n') => {}
+			'\n' => {
+				if !is_empty_record || !field.is_empty() {
+					fields.push(core::mem::take(&mut field));
+					records.push(CsvRecord {
+						line: record_line,
+						fields: core::mem::take(&mut fields),
+					});
+				}
+				is_empty_record = true;
+				line += 1;
+				record_line = line;
+			}
+			c => {
+				is_empty_record = false;
+				field.push(c);
+			}
+		}
+	}
+	if !is_empty_record || !field.is_empty() {
+		fields.push(field);
+		records.push(CsvRecord {
+			line: record_line,
+			fields,
+		});
+	}
+
+	Ok(records)
+}
+
+#[cfg(test)]
+#[test]
+fn test_csv() {
+	let records = parse_csv("a,b\r\n\n\"x, \"\"y\"\"\",\"multi\nline\"\nlast,").unwrap();
+	let fields: Vec<(usize, Vec<String>)> =
+		records.into_iter().map(|a| (a.line, a.fields)).collect();
+
+	assert_eq!(
+		fields,
+		[
+			(1, vec!["a".into(), "b".into()]),
+			(3, vec!["x, \"y\"".into(), "multi\nline".into()]),
+			(5, vec!["last".into(), "".into()]),
+		]
+	);
+	assert!(parse_csv("a,\"b").is_err());
+}
@@ -0,0 +1,265 @@
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+/// `KEY=VALUE` from the file, `PORT:u16=8080` sets the type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvEntry {
+	pub line: usize,
+	pub key: String,
+	pub ty: Option<String>,
+	pub value: String,
+}
+
+/// The file cannot be parsed, `line` is where the entry starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvError {
+	pub line: usize,
+	pub msg: String,
+}
+
+/// Reading the file by characters, counting lines.
+struct Reader<'a> {
+	chars: &'a [char],
+	i: usize,
+	line: usize,
+}
+
+impl Reader<'_> {
+	#[inline]
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.i).copied()
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.i += 1;
+		if c == '\n' {
+			self.line += 1;
+		}
+
+		Some(c)
+	}
+
+	/// Skip the rest of the line including the line break.
+	fn skip_line(&mut self) {
+		while let Some(c) = self.next() {
+			if c == '\n' {
+				break;
+			}
+		}
+	}
+
+	/// Read `NAME` or `{NAME}` after `$` and add the value of the key
+	/// defined above.
+	fn expand(&mut self, entries: &[KvEntry], result: &mut String) -> Result<(), String> {
+		let braced = self.peek() == Some('{');
+		if braced {
+			self.next();
+		}
+		let mut name = String::new();
+		while let Some(c) = self
+			.peek()
+			.filter(|a| *a == '_' || a.is_ascii_alphanumeric())
+		{
+			name.push(c);
+			self.next();
+		}
+		if braced && self.next() != Some('}') {
+			return Err("`}` was expected after `${NAME`".into());
+		}
+		if name.is_empty() {
+			// `$` alone is kept.
+			result.push('$');
+			if braced {
+				result.push_str("{}");
+			}
+			return Ok(());
+		}
+
+		match entries.iter().rfind(|a| a.key == name) {
+			Some(a) => {
+				result.push_str(&a.value);
+				Ok(())
+			}
+			None => Err(format!(
+				"`{name}` is not defined, only the keys above can be used in `${{{name}}}`"
+			)),
+		}
+	}
+}
+
+/// Parse the dotenv file: `KEY=VALUE` lines, `#` comments, `export KEY=VALUE`,
+/// `'raw'` and `"escaped"` values (the latter can span several lines),
+/// `${KEY}` and `$KEY` are replaced by the values of the keys above.
+///
+/// (A key defined again replaces the previous value)
+pub fn parse_dotenv(data: &str) -> Result<Vec<KvEntry>, KvError> {
+	let chars: Vec<char> = data.chars().collect();
+	let mut r = Reader {
+		chars: &chars,
+		i: 0,
+		line: 1,
+	};
+	let mut entries: Vec<KvEntry> = Vec::new();
+
+	loop {
+		while r.peek().is_some_and(char::is_whitespace) {
+			r.next();
+		}
+		match r.peek() {
+			None => break,
+			Some('#') => {
+				r.skip_line();
+				continue;
+			}
+			Some(..) => {}
+		}
+
+		let line = r.line;
+		let err = |msg: String| KvError { line, msg };
+		let mut key = String::new();
+		loop {
+			match r.next() {
+				Some('=') => break,
+				Some('\n') | None => {
+					return Err(err(format!("`=` was expected after `{}`", key.trim())));
+				}
+				Some(c) => key.push(c),
+			}
+		}
+		let key = key.trim();
+		let key = key.strip_prefix("export ").map_or(key, str::trim_start);
+		let (key, ty) = match key.split_once(':') {
+			Some((key, ty)) => (key.trim_end(), Some(ty.trim().to_string())),
+			None => (key, None),
+		};
+		let is_valid_key = key.starts_with(|a: char| a == '_' || a.is_ascii_alphabetic())
+			&& key.chars().all(|a| a == '_' || a.is_ascii_alphanumeric());
+		if !is_valid_key {
+			return Err(err(format!(
+				"`{key}` is not a valid key, expected letters, digits and `_`"
+			)));
+		}
+
+		while r.peek().is_some_and(|a| a == ' ' || a == '\t') {
+			r.next();
+		}
+		let mut value = String::new();
+		match r.peek() {
+			Some(quote @ ('"' | '\'')) => {
+				r.next();
+				loop {
+					match (quote, r.next()) {
+						(_, None) => {
+							return Err(err(format!("the closing `{quote}` was not found")));
+						}
+						(_, Some(c)) if c == quote => break,
+						('"', Some('\\')) => match r.next() {
+							Some('n') => value.push('\n'),
+							Some('r') => value.push('\r'),
+							Some('t') => value.push('\t'),
+							Some(c @ ('\\' | '"' | '$')) => value.push(c),
+							Some(c) => {
+								value.push('\\');
+								value.push(c);
+							}
+							None => {}
+						},
+						('"', Some('$')) => r.expand(&entries, &mut value).map_err(err)?,
+						(_, Some(c)) => value.push(c),
+					}
+				}
+
+				while r.peek().is_some_and(|a| a == ' ' || a == '\t' || a == '\r') {
+					r.next();
+				}
+				match r.peek() {
+					Some('#') => r.skip_line(),
+					Some('\n') | None => {}
+					Some(c) => {
+						return Err(err(format!(
+							"unexpected `{c}` after the quoted value of `{key}`"
+						)));
+					}
+				}
+			}
+			_ => {
+				while let Some(c) = r.peek().filter(|a| *a != '\n') {
+					r.next();
+					match c {
+						// `KEY=value # comment`
+						'#' if value.ends_with([' ', '\t']) || value.is_empty() => {
+							r.skip_line();
+							break;
+						}
+						'$' => r.expand(&entries, &mut value).map_err(err)?,
+						c => value.push(c),
+					}
+				}
+				value.truncate(value.trim_end().len());
+			}
+		}
+
+		let entry = KvEntry {
+			line,
+			key: key.to_string(),
+			ty,
+			value,
+		};
+		match entries.iter_mut().find(|a| a.key == entry.key) {
+			Some(old) => *old = entry,
+			None => entries.push(entry),
+		}
+	}
+
+	Ok(entries)
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_dotenv() {
+	let entries = parse_dotenv(
+		"# Settings\n\
+		HOST=localhost # the host\n\
+		export PORT:u16 = 8080\n\
+		URL=\"http://${HOST}:$PORT/\\$x\"\n\
+		RAW='${HOST} \\n'\n\
+		\n\
+		MULTI=\"a\n\
+		b\"\n\
+		HOST=example.com\n\
+		EMPTY=\n",
+	)
+	.unwrap();
+	let list: Vec<(usize, &str, Option<&str>, &str)> = entries
+		.iter()
+		.map(|a| (a.line, &*a.key, a.ty.as_deref(), &*a.value))
+		.collect();
+	assert_eq!(
+		list,
+		[
+			(9, "HOST", None, "example.com"),
+			(3, "PORT", Some("u16"), "8080"),
+			(4, "URL", None, "http://localhost:8080/$x"),
+			(5, "RAW", None, "${HOST} \\n"),
+			(7, "MULTI", None, "a\nb"),
+			(10, "EMPTY", None, ""),
+		]
+	);
+
+	assert_eq!(parse_dotenv("A=1\nB=${C}").unwrap_err().line, 2);
+	assert_eq!(parse_dotenv("A=1\nB").unwrap_err().line, 2);
+	assert_eq!(parse_dotenv("A=\"1").unwrap_err().line, 1);
+	assert_eq!(
+		parse_dotenv("A=\"1").unwrap_err().msg,
+		"the closing `\"` was not found"
+	);
+	assert_eq!(
+		parse_dotenv("A='1' 2").unwrap_err().msg,
+		"unexpected `2` after the quoted value of `A`"
+	);
+	assert_eq!(parse_dotenv("1A=1").unwrap_err().line, 1);
+}
@@ -0,0 +1,228 @@
+use alloc::{
+	format,
+	string::{String, ToString},
+};
+
+/// Text encoding of the file, `#str("a.txt"; encoding = "utf-16le")`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+	Utf8,
+	/// UTF-16 with the byte order from the BOM (little-endian without it).
+	Utf16,
+	Utf16Le,
+	Utf16Be,
+	/// ISO-8859-1, every byte is the code point.
+	Latin1,
+	Windows1252,
+	/// UTF-8, invalid sequences are replaced by `U+FFFD`.
+	Lossy,
+}
+
+impl Encoding {
+	/// Names accepted by `from_name`, for messages.
+	pub const NAMES: &'static str =
+		"`utf-8`, `utf-16`, `utf-16le`, `utf-16be`, `latin1`, `windows-1252`, `lossy`";
+
+	/// Get the encoding by its name (case-insensitive, `_` is the same as `-`).
+	pub fn from_name(name: &str) -> Option<Self> {
+		let name = name.to_ascii_lowercase().replace('_', "-");
+		Some(match name.as_str() {
+			"utf-8" | "utf8" => Self::Utf8,
+			"utf-16" | "utf16" => Self::Utf16,
+			"utf-16le" | "utf16le" => Self::Utf16Le,
+			"utf-16be" | "utf16be" => Self::Utf16Be,
+			"latin1" | "latin-1" | "iso-8859-1" => Self::Latin1,
+			"windows-1252" | "cp1252" => Self::Windows1252,
+			"lossy" => Self::Lossy,
+			_ => return None,
+		})
+	}
+}
+
+/// Characters of Windows-1252 for the bytes `0x80..=0x9F`, the undefined
+/// ones are the C1 control characters (as Windows decodes them).
+const WINDOWS_1252: [char; 32] = [
+	'\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+	'\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+	'\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+	'\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+/// Decode UTF-16 without the BOM.
+fn decode_utf16(data: &[u8], is_be: bool) -> Result<String, String> {
+	if data.len() % 2 != 0 {
+		return Err(format!(
+			"UTF-16 data has an odd length ({} bytes)",
+			data.len()
+		));
+	}
+	let units = data.chunks_exact(2).map(|a| match is_be {
+		true => u16::from_be_bytes([a[0], a[1]]),
+		false => u16::from_le_bytes([a[0], a[1]]),
+	});
+
+	char::decode_utf16(units)
+		.enumerate()
+		.map(|(i, a)| {
+			a.map_err(|e| {
+				format!(
+					"unpaired UTF-16 surrogate 0x{:04X} at byte {}",
+					e.unpaired_surrogate(),
+					i * 2
+				)
+			})
+		})
+		.collect()
+}
+
+/// Byte order mark at the start of the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bom {
+	Utf8,
+	Utf16 { is_be: bool },
+}
+
+impl Bom {
+	fn detect(data: &[u8]) -> Option<Self> {
+		match data {
+			[0xEF, 0xBB, 0xBF, ..] => Some(Self::Utf8),
+			[0xFF, 0xFE, ..] => Some(Self::Utf16 { is_be: false }),
+			[0xFE, 0xFF, ..] => Some(Self::Utf16 { is_be: true }),
+			_ => None,
+		}
+	}
+
+	const fn len(self) -> usize {
+		match self {
+			Self::Utf8 => 3,
+			Self::Utf16 { .. } => 2,
+		}
+	}
+}
+
+/// Decode the contents of the file: a UTF-16 BOM is detected when the
+/// encoding is not specified (or is `utf-16`), UTF-8 is used otherwise.
+///
+/// (The BOM of the specified encoding is removed, as well as the UTF-16 BOM
+/// deciding the decoding. Without the encoding, the UTF-8 BOM is kept as
+/// `U+FEFF`, like `include_str!` does, the `strip_bom` flag removes it.
+/// A UTF-16 BOM contradicting `utf-16le` or `utf-16be` is an error)
+pub fn decode_text(data: &[u8], encoding: Option<Encoding>) -> Result<String, String> {
+	let bom = Bom::detect(data);
+	let without_bom = &data[bom.map_or(0, Bom::len)..];
+
+	match (encoding, bom) {
+		(None | Some(Encoding::Utf16), Some(Bom::Utf16 { is_be })) => {
+			decode_utf16(without_bom, is_be)
+		}
+		(Some(Encoding::Utf16Le), Some(Bom::Utf16 { is_be: false })) => {
+			decode_utf16(without_bom, false)
+		}
+		(Some(Encoding::Utf16Be), Some(Bom::Utf16 { is_be: true })) => {
+			decode_utf16(without_bom, true)
+		}
+		// The other byte order than the BOM.
+		(Some(Encoding::Utf16Le | Encoding::Utf16Be), Some(Bom::Utf16 { is_be })) => {
+			let (order, name) = match is_be {
+				true => ("big-endian", "utf-16le"),
+				false => ("little-endian", "utf-16be"),
+			};
+			Err(format!(
+				"the BOM is {order}, but the encoding is `{name}`, specify `encoding = \"utf-16\"` to follow the BOM"
+			))
+		}
+		(Some(Encoding::Utf16 | Encoding::Utf16Le), _) => decode_utf16(data, false),
+		(Some(Encoding::Utf16Be), _) => decode_utf16(data, true),
+		(Some(Encoding::Latin1), _) => Ok(data.iter().map(|a| *a as char).collect()),
+		(Some(Encoding::Windows1252), _) => Ok(data
+			.iter()
+			.map(|a| match a {
+				0x80..=0x9F => WINDOWS_1252[(a - 0x80) as usize],
+				a => *a as char,
+			})
+			.collect()),
+		(Some(Encoding::Lossy), Some(Bom::Utf8)) => {
+			Ok(String::from_utf8_lossy(without_bom).into_owned())
+		}
+		(Some(Encoding::Lossy), _) => Ok(String::from_utf8_lossy(data).into_owned()),
+		(None | Some(Encoding::Utf8), bom) => {
+			let skip = match (encoding, bom) {
+				(Some(Encoding::Utf8), Some(Bom::Utf8)) => Bom::Utf8.len(),
+				_ => 0,
+			};
+			match core::str::from_utf8(&data[skip..]) {
+				Ok(a) => Ok(a.to_string()),
+				Err(e) => Err(format!(
+					"invalid UTF-8 at byte {}, specify the encoding: `encoding = \"windows-1252\"`",
+					e.valid_up_to() + skip
+				)),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_text() {
+	let utf16le = [0xFF, 0xFE, b'h', 0, b'i', 0, 0xAC, 0x20];
+	assert_eq!(decode_text(&utf16le, None).as_deref(), Ok("hi\u{20AC}"));
+	assert_eq!(
+		decode_text(&[0xFE, 0xFF, 0, b'h'], Some(Encoding::Utf16)).as_deref(),
+		Ok("h")
+	);
+	assert_eq!(
+		decode_text(&[0, b'h'], Some(Encoding::Utf16Be)).as_deref(),
+		Ok("h")
+	);
+	assert_eq!(
+		decode_text(&[0xFF, 0xFE, b'h', 0], Some(Encoding::Utf16Le)).as_deref(),
+		Ok("h")
+	);
+	assert_eq!(
+		decode_text(&[0xFE, 0xFF, 0, b'h'], Some(Encoding::Utf16Be)).as_deref(),
+		Ok("h")
+	);
+	// The BOM contradicts the byte order.
+	assert!(decode_text(&[0xFE, 0xFF, 0, b'h'], Some(Encoding::Utf16Le)).is_err());
+	assert!(decode_text(&[0xFF, 0xFE, b'h', 0], Some(Encoding::Utf16Be)).is_err());
+	assert!(decode_text(&[b'h', 0, 0], Some(Encoding::Utf16Le)).is_err());
+	assert!(decode_text(&[0x00, 0xD8], Some(Encoding::Utf16Le)).is_err());
+
+	assert_eq!(
+		decode_text(b"caf\xE9", Some(Encoding::Latin1)).as_deref(),
+		Ok("caf\u{E9}")
+	);
+	assert_eq!(
+		decode_text(b"\x80 \x93a\x94", Some(Encoding::Windows1252)).as_deref(),
+		Ok("\u{20AC} \u{201C}a\u{201D}")
+	);
+	assert_eq!(
+		decode_text(b"a\xFFb", Some(Encoding::Lossy)).as_deref(),
+		Ok("a\u{FFFD}b")
+	);
+	assert!(decode_text(b"a\xFFb", None).is_err());
+
+	// The UTF-8 BOM is removed only with the encoding.
+	assert_eq!(
+		decode_text(b"\xEF\xBB\xBFa", None).as_deref(),
+		Ok("\u{FEFF}a")
+	);
+	assert_eq!(
+		decode_text(b"\xEF\xBB\xBFa", Some(Encoding::Utf8)).as_deref(),
+		Ok("a")
+	);
+	assert_eq!(
+		decode_text(b"\xEF\xBB\xBFa\xFF", Some(Encoding::Lossy)).as_deref(),
+		Ok("a\u{FFFD}")
+	);
+	assert_eq!(
+		decode_text(b"\xEF\xBB\xBFa\xFF", Some(Encoding::Utf8)),
+		Err(String::from(
+			"invalid UTF-8 at byte 4, specify the encoding: `encoding = \"windows-1252\"`"
+		))
+	);
+
+	assert_eq!(Encoding::from_name("UTF_16LE"), Some(Encoding::Utf16Le));
+	assert_eq!(Encoding::from_name("cp1252"), Some(Encoding::Windows1252));
+	assert_eq!(Encoding::from_name("koi8-r"), None);
+}
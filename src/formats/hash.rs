@@ -0,0 +1,33 @@
+use alloc::{format, string::String};
+
+const FNV64_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV64_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Stable 64-bit FNV-1a hash of the data.
+///
+/// (Does not depend on the platform or the version of the compiler)
+pub const fn fnv1a64(data: &[u8]) -> u64 {
+	let mut hash = FNV64_OFFSET_BASIS;
+
+	let mut i = 0;
+	while i < data.len() {
+		hash ^= data[i] as u64;
+		hash = hash.wrapping_mul(FNV64_PRIME);
+		i += 1;
+	}
+
+	hash
+}
+
+/// A strong `ETag` value (including the double quotes) for the data.
+pub fn make_etag(data: &[u8]) -> String {
+	format!("\"{:016x}\"", fnv1a64(data))
+}
+
+#[cfg(test)]
+#[test]
+fn test_fnv1a64() {
+	assert_eq!(fnv1a64(b""), 0xcbf2_9ce4_8422_2325);
+	assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
+	assert_eq!(make_etag(b"a"), "\"af63dc4c8601ec8c\"");
+}
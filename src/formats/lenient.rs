@@ -0,0 +1,215 @@
+use alloc::{string::String, vec::Vec};
+use core::str::FromStr;
+use proc_macro2::{
+	Delimiter, Group, Literal, Punct, Spacing, TokenStream as TokenStream2, TokenTree as TokenTree2,
+};
+
+/// Characters that are punctuation for the Rust lexer.
+pub const PUNCT_CHARS: &str = "~!@#$%^&*-=+|;:,<.>/?";
+
+/// Add the trees of the chunk of text, or a string literal with it
+/// if the Rust lexer does not accept it.
+fn push_chunk(trees: &mut Vec<TokenTree2>, chunk: &str) {
+	match TokenStream2::from_str(chunk) {
+		Ok(stream) => trees.extend(stream),
+		Err(..) => trees.push(TokenTree2::Literal(Literal::string(chunk))),
+	}
+}
+
+/// The length of the quoted literal starting at `i` (`"a"`, `'a'`),
+/// `None` if it is not closed.
+fn quoted_len(chars: &[char], i: usize, quote: char) -> Option<usize> {
+	let mut j = i + 1;
+	while let Some(&a) = chars.get(j) {
+		match a {
+			'\\' => j += 2,
+			a if a == quote => return Some(j + 1 - i),
+			'\n' if quote == '\'' => return None,
+			_ => j += 1,
+		}
+	}
+
+	None
+}
+
+/// The group being collected by `lex_lenient`.
+struct OpenGroup {
+	open: char,
+	trees: Vec<TokenTree2>,
+}
+
+impl OpenGroup {
+	/// The closing character of the group.
+	const fn close(&self) -> char {
+		match self.open {
+			'(' => ')',
+			'[' => ']',
+			_ => '}',
+		}
+	}
+
+	/// The delimiter of the group.
+	const fn delimiter(&self) -> Delimiter {
+		match self.open {
+			'(' => Delimiter::Parenthesis,
+			'[' => Delimiter::Bracket,
+			_ => Delimiter::Brace,
+		}
+	}
+
+	/// Close the group, `trees` are its contents.
+	fn close_into(self, trees: &mut Vec<TokenTree2>) {
+		let delimiter = self.delimiter();
+		let inner = core::mem::replace(trees, self.trees);
+		trees.push(TokenTree2::Group(Group::new(
+			delimiter,
+			TokenStream2::from_iter(inner),
+		)));
+	}
+
+	/// The group is never closed, its bracket becomes a string.
+	fn unclose_into(self, trees: &mut Vec<TokenTree2>) {
+		let inner = core::mem::replace(trees, self.trees);
+		trees.push(make_str(self.open));
+		trees.extend(inner);
+	}
+}
+
+/// The character the Rust lexer does not accept as a string literal.
+fn make_str(c: char) -> TokenTree2 {
+	TokenTree2::Literal(Literal::string(c.encode_utf8(&mut [0; 4])))
+}
+
+/// Split arbitrary text into trees without ever failing: what the Rust
+/// lexer does not accept (unclosed quotes, unbalanced brackets, `` ` ``,
+/// `\`, other characters) becomes string literals with this text.
+///
+/// (Text that the Rust lexer accepts gives the same trees as `#tt`)
+pub fn lex_lenient(data: &str) -> TokenStream2 {
+	if let Ok(stream) = TokenStream2::from_str(data) {
+		return stream;
+	}
+
+	let chars: Vec<char> = data.chars().collect();
+	let is_ident_char = |a: char| a == '_' || a.is_alphanumeric();
+
+	let mut stack = Vec::new();
+	let mut trees = Vec::new();
+	let mut i = 0;
+	while let Some(&c) = chars.get(i) {
+		let next = chars.get(i + 1).copied();
+		let len = match c {
+			c if c.is_whitespace() => {
+				i += 1;
+				continue;
+			}
+			'/' if next == Some('/') => {
+				i += chars[i..].iter().take_while(|a| **a != '\n').count();
+				continue;
+			}
+			'/' if next == Some('*') => {
+				i = match chars[i + 2..].windows(2).position(|a| a == ['*', '/']) {
+					Some(end) => i + 2 + end + 2,
+					None => chars.len(),
+				};
+				continue;
+			}
+			'(' | '[' | '{' => {
+				stack.push(OpenGroup {
+					open: c,
+					trees: core::mem::take(&mut trees),
+				});
+				i += 1;
+				continue;
+			}
+			')' | ']' | '}' => {
+				match stack.iter().rposition(|a| a.close() == c) {
+					Some(pos) => {
+						// Groups opened after the matching one are not closed,
+						// their brackets become strings.
+						for group in stack.drain(pos + 1..).rev() {
+							group.unclose_into(&mut trees);
+						}
+						if let Some(group) = stack.pop() {
+							group.close_into(&mut trees);
+						}
+					}
+					None => trees.push(make_str(c)),
+				}
+				i += 1;
+				continue;
+			}
+			c if PUNCT_CHARS.contains(c) => {
+				let spacing = match next {
+					Some(a) if PUNCT_CHARS.contains(a) => Spacing::Joint,
+					_ => Spacing::Alone,
+				};
+				trees.push(TokenTree2::Punct(Punct::new(c, spacing)));
+				i += 1;
+				continue;
+			}
+			'"' => quoted_len(&chars, i, '"').unwrap_or(1),
+			'\'' if next.is_some_and(is_ident_char) => {
+				let len = 1 + chars[i + 1..]
+					.iter()
+					.take_while(|a| is_ident_char(**a))
+					.count();
+				match chars.get(i + len) {
+					// `'a'`, `'abc'`.
+					Some('\'') => len + 1,
+					// `'a` lifetime.
+					_ => len,
+				}
+			}
+			'\'' => quoted_len(&chars, i, '\'').unwrap_or(1),
+			c if is_ident_char(c) => {
+				let mut len = chars[i..]
+					.iter()
+					.enumerate()
+					.take_while(|(n, a)| {
+						is_ident_char(**a)
+							// `1.5`
+							|| **a == '.'
+								&& c.is_ascii_digit()
+								&& chars.get(i + n + 1).is_some_and(|a| a.is_ascii_digit())
+					})
+					.count();
+				// `b"a"`, `c"a"`, `b'a'`.
+				if let Some(quote @ ('"' | '\'')) = chars.get(i + len).copied() {
+					if let Some(qlen) = quoted_len(&chars, i + len, quote) {
+						len += qlen;
+					}
+				}
+				len
+			}
+			_ => 1,
+		};
+
+		let chunk: String = chars[i..i + len].iter().collect();
+		push_chunk(&mut trees, &chunk);
+		i += len;
+	}
+
+	// Unclosed groups, their brackets become strings.
+	while let Some(group) = stack.pop() {
+		group.unclose_into(&mut trees);
+	}
+
+	TokenStream2::from_iter(trees)
+}
+
+#[cfg(test)]
+#[test]
+fn test_lex_lenient() {
+	let lex = |a: &str| lex_lenient(a).to_string();
+
+	assert_eq!(lex("a + 1"), "a + 1");
+	assert_eq!(lex("say \"hi"), "say \"\\\"\" hi");
+	assert_eq!(lex("`cmd` x"), "\"`\" cmd \"`\" x");
+	assert_eq!(lex("a \\ b"), "a \"\\\\\" b");
+	assert_eq!(lex("f(a] b"), "f \"(\" a \"]\" b");
+	assert_eq!(lex("[a (b] c"), "[a \"(\" b] c");
+	assert_eq!(lex("x 'abc' y 'a `"), "x \"'abc'\" y 'a \"`\"");
+	assert_eq!(lex("1.5 \"ok\" // `\n` /* ` */"), "1.5 \"ok\" \"`\"");
+	assert_eq!(lex("a => b `"), "a => b \"`\"");
+}
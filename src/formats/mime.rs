@@ -0,0 +1,61 @@
+use std::path::Path;
+
+/// The type used when the extension of the file is unknown.
+pub const DEFAULT_MIME: &str = "application/octet-stream";
+
+/// Built-in table of extensions (lowercase, without the dot) and their MIME types.
+const MIME_TABLE: &[(&str, &str)] = &[
+	// text
+	("html", "text/html; charset=utf-8"),
+	("htm", "text/html; charset=utf-8"),
+	("css", "text/css; charset=utf-8"),
+	("js", "text/javascript; charset=utf-8"),
+	("mjs", "text/javascript; charset=utf-8"),
+	("txt", "text/plain; charset=utf-8"),
+	("csv", "text/csv; charset=utf-8"),
+	("md", "text/markdown; charset=utf-8"),
+	("xml", "application/xml"),
+	("json", "application/json"),
+	("map", "application/json"),
+	("webmanifest", "application/manifest+json"),
+	("wasm", "application/wasm"),
+	("pdf", "application/pdf"),
+	("zip", "application/zip"),
+	("gz", "application/gzip"),
+	// images
+	("png", "image/png"),
+	("jpg", "image/jpeg"),
+	("jpeg", "image/jpeg"),
+	("gif", "image/gif"),
+	("webp", "image/webp"),
+	("avif", "image/avif"),
+	("svg", "image/svg+xml"),
+	("ico", "image/x-icon"),
+	("bmp", "image/bmp"),
+	// fonts
+	("woff", "font/woff"),
+	("woff2", "font/woff2"),
+	("ttf", "font/ttf"),
+	("otf", "font/otf"),
+	// media
+	("mp3", "audio/mpeg"),
+	("ogg", "audio/ogg"),
+	("wav", "audio/wav"),
+	("mp4", "video/mp4"),
+	("webm", "video/webm"),
+];
+
+/// Guess the MIME type of a file by its extension.
+///
+/// (Unknown extensions are reported as `application/octet-stream`)
+pub fn guess_mime(path: &Path) -> &'static str {
+	let ext = match path.extension().and_then(|a| a.to_str()) {
+		Some(a) => a,
+		None => return DEFAULT_MIME,
+	};
+
+	MIME_TABLE
+		.iter()
+		.find(|(e, _)| e.eq_ignore_ascii_case(ext))
+		.map_or(DEFAULT_MIME, |(_, mime)| mime)
+}
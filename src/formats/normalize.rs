@@ -0,0 +1,87 @@
+use alloc::string::String;
+
+/// Remove the indentation common to all non-blank lines, whitespace-only
+/// lines become empty.
+fn dedent(data: &str) -> String {
+	let indent = |a: &str| a.len() - a.trim_start_matches([' ', '\t']).len();
+
+	let mut common: Option<&str> = None;
+	for line in data.lines().filter(|a| !a.trim().is_empty()) {
+		let prefix = &line[..indent(line)];
+		common = Some(match common {
+			None => prefix,
+			Some(common) => {
+				let len = common
+					.bytes()
+					.zip(prefix.bytes())
+					.take_while(|(a, b)| a == b)
+					.count();
+				&common[..len]
+			}
+		});
+	}
+	let common = common.unwrap_or_default();
+
+	let mut result = String::with_capacity(data.len());
+	for line in data.split_inclusive('\n') {
+		match line.strip_prefix(common) {
+			_ if line.trim().is_empty() => {
+				result.push_str(&line[line.trim_end_matches(['\r', '\n']).len()..])
+			}
+			Some(a) => result.push_str(a),
+			None => result.push_str(line),
+		}
+	}
+
+	result
+}
+
+/// Flags of `normalize_text`, accepted by the directives reading text.
+pub const NORMALIZE_FLAGS: &[&str] = &["strip_bom", "lf", "dedent", "trim", "strip_newline"];
+
+/// Normalize the text of the file by the flags of the directive,
+/// `#str[lf, trim]("a.txt")`: `strip_bom`, `lf` (CRLF to LF), `dedent`,
+/// `trim`, `strip_newline` (one trailing line break), in this order.
+pub fn normalize_text(mut data: String, has_flag: impl Fn(&str) -> bool) -> String {
+	if has_flag("strip_bom") && data.starts_with('\u{FEFF}') {
+		data.drain(..'\u{FEFF}'.len_utf8());
+	}
+	if has_flag("lf") && data.contains('\r') {
+		data = data.replace("\r\n", "\n");
+	}
+	if has_flag("dedent") {
+		data = dedent(&data);
+	}
+	if has_flag("trim") {
+		data = String::from(data.trim());
+	}
+	if has_flag("strip_newline") {
+		if data.ends_with("\r\n") {
+			data.truncate(data.len() - 2);
+		} else if data.ends_with('\n') {
+			data.truncate(data.len() - 1);
+		}
+	}
+
+	data
+}
+
+#[cfg(test)]
+#[test]
+fn test_normalize_text() {
+	let normalize =
+		|data: &str, flags: &[&str]| normalize_text(String::from(data), |a| flags.contains(&a));
+
+	assert_eq!(
+		normalize("\u{FEFF}a\r\nb\r\n", &["strip_bom", "lf"]),
+		"a\nb\n"
+	);
+	assert_eq!(normalize("\u{FEFF}a\r\n", &[]), "\u{FEFF}a\r\n");
+	assert_eq!(normalize("a\r\n\r\n", &["strip_newline"]), "a\r\n");
+	assert_eq!(normalize("  a  \n\n", &["trim"]), "a");
+	assert_eq!(
+		normalize("\t\tfn a() {\n\t\t\tb\n  \n\t\t}\n", &["dedent"]),
+		"fn a() {\n\tb\n\n}\n"
+	);
+	assert_eq!(normalize("    a\n  b\n", &["dedent"]), "  a\nb\n");
+}
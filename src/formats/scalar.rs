@@ -0,0 +1,196 @@
+use alloc::{format, string::String, vec::Vec};
+use core::num::IntErrorKind;
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2, TokenTree as TokenTree2};
+
+/// Parsing a scalar value from the contents of a file with strict validation.
+pub trait ParseScalar {
+	/// Name of the type to display in errors.
+	const NAME: &'static str;
+
+	/// Parse the (already trimmed) text and return a suffixed literal.
+	fn parse_scalar(a: &str) -> Result<TokenTree2, String>;
+}
+
+macro_rules! impl_parse_int {
+	[ $( $t:ident => $suffixed:ident ),* $(,)? ] => {
+		$(
+			impl ParseScalar for $t {
+				const NAME: &'static str = stringify!($t);
+
+				fn parse_scalar(a: &str) -> Result<TokenTree2, String> {
+					let (sign, digits) = match a.strip_prefix('-') {
+						Some(a) => ("-", a),
+						None => ("", a),
+					};
+					let (radix, digits) = match digits.get(..2) {
+						Some("0x") => (16, &digits[2..]),
+						Some("0o") => (8, &digits[2..]),
+						Some("0b") => (2, &digits[2..]),
+						_ => (10, digits),
+					};
+					let digits: String = digits.chars().filter(|a| *a != '_').collect();
+
+					match $t::from_str_radix(&format!("{sign}{digits}"), radix) {
+						Ok(a) => Ok(TokenTree2::Literal(Literal::$suffixed(a))),
+						Err(e) => match e.kind() {
+							IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => Err(format!(
+								"`{a}` is out of range for `{}` ({}..={})",
+								Self::NAME,
+								$t::MIN,
+								$t::MAX
+							)),
+							_ => Err(format!("`{a}` is not a valid `{}`", Self::NAME)),
+						},
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_parse_int! {
+	u8 => u8_suffixed,
+	u16 => u16_suffixed,
+	u32 => u32_suffixed,
+	u64 => u64_suffixed,
+	u128 => u128_suffixed,
+	usize => usize_suffixed,
+	i8 => i8_suffixed,
+	i16 => i16_suffixed,
+	i32 => i32_suffixed,
+	i64 => i64_suffixed,
+	i128 => i128_suffixed,
+	isize => isize_suffixed,
+}
+
+macro_rules! impl_parse_float {
+	[ $( $t:ident => $suffixed:ident ),* $(,)? ] => {
+		$(
+			impl ParseScalar for $t {
+				const NAME: &'static str = stringify!($t);
+
+				fn parse_scalar(a: &str) -> Result<TokenTree2, String> {
+					let digits: String = a.chars().filter(|a| *a != '_').collect();
+					// `inf`, `NaN` cannot be written as a literal.
+					let is_number = digits.starts_with(|a: char| a == '-' || a.is_ascii_digit())
+						&& !digits.contains(['i', 'I', 'n', 'N']);
+
+					match (is_number, digits.parse::<$t>()) {
+						(true, Ok(v)) if v.is_finite() => Ok(TokenTree2::Literal(Literal::$suffixed(v))),
+						(true, Ok(..)) => Err(format!("`{a}` is out of range for `{}`", Self::NAME)),
+						_ => Err(format!("`{a}` is not a valid `{}`", Self::NAME)),
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_parse_float! {
+	f32 => f32_suffixed,
+	f64 => f64_suffixed,
+}
+
+impl ParseScalar for bool {
+	const NAME: &'static str = "bool";
+
+	fn parse_scalar(a: &str) -> Result<TokenTree2, String> {
+		match a {
+			"true" | "false" => Ok(TokenTree2::Ident(Ident::new(a, Span::call_site()))),
+			_ => Err(format!(
+				"`{a}` is not a valid `bool`, expected `true` or `false`"
+			)),
+		}
+	}
+}
+
+impl ParseScalar for char {
+	const NAME: &'static str = "char";
+
+	fn parse_scalar(a: &str) -> Result<TokenTree2, String> {
+		// The symbol can be written both as is and as a literal `'a'`.
+		if a.len() > 2 && a.starts_with('\'') && a.ends_with('\'') {
+			if let Ok(lit) = syn::parse_str::<syn::LitChar>(a) {
+				return Ok(TokenTree2::Literal(Literal::character(lit.value())));
+			}
+		}
+
+		let mut chars = a.chars();
+		match (chars.next(), chars.next()) {
+			(Some(c), None) => Ok(TokenTree2::Literal(Literal::character(c))),
+			_ => Err(format!(
+				"`{a}` is not a valid `char`, expected exactly one symbol"
+			)),
+		}
+	}
+}
+
+/// Parse the text as the scalar type with the given name (`u16`, `bool`, ...),
+/// `None` if there is no such type.
+pub fn parse_scalar_by_name(ty: &str, a: &str) -> Option<Result<TokenTree2, String>> {
+	Some(match ty {
+		"u8" => u8::parse_scalar(a),
+		"u16" => u16::parse_scalar(a),
+		"u32" => u32::parse_scalar(a),
+		"u64" => u64::parse_scalar(a),
+		"u128" => u128::parse_scalar(a),
+		"usize" => usize::parse_scalar(a),
+		"i8" => i8::parse_scalar(a),
+		"i16" => i16::parse_scalar(a),
+		"i32" => i32::parse_scalar(a),
+		"i64" => i64::parse_scalar(a),
+		"i128" => i128::parse_scalar(a),
+		"isize" => isize::parse_scalar(a),
+		"f32" => f32::parse_scalar(a),
+		"f64" => f64::parse_scalar(a),
+		"bool" => bool::parse_scalar(a),
+		"char" => char::parse_scalar(a),
+		_ => return None,
+	})
+}
+
+/// Checks that the stream is exactly one literal (a negative number is also allowed)
+/// and returns it.
+pub fn expect_one_literal(stream: TokenStream2) -> Result<TokenStream2, String> {
+	let all: Vec<TokenTree2> = stream.clone().into_iter().collect();
+	match all.as_slice() {
+		[TokenTree2::Literal(..)] => Ok(stream),
+		[TokenTree2::Ident(i)] if i == "true" || i == "false" => Ok(stream),
+		[TokenTree2::Punct(p), TokenTree2::Literal(l)]
+			if p.as_char() == '-' && l.to_string().starts_with(|a: char| a.is_ascii_digit()) =>
+		{
+			Ok(stream)
+		}
+		[] => Err("expected exactly one literal, found nothing".into()),
+		_ => Err(format!(
+			"expected exactly one literal, found `{stream}` ({} trees)",
+			all.len()
+		)),
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_scalar() {
+	assert_eq!(u8::parse_scalar("0xff").unwrap().to_string(), "255u8");
+	assert_eq!(i32::parse_scalar("-1_000").unwrap().to_string(), "-1000i32");
+	assert!(
+		u8::parse_scalar("256")
+			.unwrap_err()
+			.contains("out of range")
+	);
+	assert!(
+		u32::parse_scalar("100500, 1")
+			.unwrap_err()
+			.contains("not a valid")
+	);
+	assert_eq!(f64::parse_scalar("1.5").unwrap().to_string(), "1.5f64");
+	assert!(f64::parse_scalar("inf").is_err());
+	assert!(
+		f32::parse_scalar("1e39")
+			.unwrap_err()
+			.contains("out of range")
+	);
+	assert_eq!(char::parse_scalar("'\\n'").unwrap().to_string(), "'\\n'");
+	assert!(bool::parse_scalar("yes").is_err());
+}
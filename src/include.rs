@@ -1,19 +1,47 @@
 use crate::{
-	PointTrack,
-	exprs::literal::ExprLit,
+	PointTrack, SearchState,
+	exprs::{
+		args::{IncludeArgs, path_span},
+		ident::make_ident,
+		kind::SyntaxKind,
+		literal::ExprLit,
+	},
+	formats::{
+		c_header::{CSkipped, parse_c_header},
+		c_source::{describe_alterations, prepare_c_source},
+		case::Case,
+		dotenv::parse_dotenv,
+		encoding::{Encoding, decode_text},
+		hash::make_etag,
+		lenient::lex_lenient,
+		mime::guess_mime,
+		normalize::{NORMALIZE_FLAGS, normalize_text},
+		scalar::{ParseScalar, expect_one_literal, parse_scalar_by_name},
+	},
+	throw_sg_err, track_file,
 	trees::{
+		flat::{as_flat_group, make_flat_group},
 		group::stream_stringify_with_fns,
-		loader::{LoadFileAndAutoMakeTreeErr, load_file_and_automake_tree_with_fns},
+		loader::{
+			LoadFileAndAutoMakeTreeErr, load_file_and_automake_tree_with_fns,
+			load_file_to_bytes_with_fns, load_file_to_string_with_fns,
+		},
 		null::make_null_group,
 		result::TreeResult,
+		tq,
 	},
 };
+use core::{cell::Cell, str::FromStr};
 use proc_macro2::{
-	Delimiter, Group, Literal, Span, TokenStream as TokenStream2, TokenTree as TokenTree2,
+	Delimiter, Group, Ident, Literal, Span, TokenStream as TokenStream2, TokenTree as TokenTree2,
+};
+use quote::{quote, quote_spanned};
+use std::{
+	borrow::Cow,
+	io::Error as IOError,
+	path::{Path, PathBuf},
 };
-use quote::ToTokens;
-use std::{borrow::Cow, io::Error as IOError, path::Path};
-use std::{fs::File, io::Read};
+use syn::{Lit, LitByteStr, LitStr};
 
 /// A trait that specifies the final behavior for the `include` macro.
 pub trait BehMacroInclude {
@@ -23,13 +51,182 @@ pub trait BehMacroInclude {
 	/// Assembly of the final tree.
 	fn make_tree(
 		arg0: &ExprLit,
+		args: &IncludeArgs,
 		point_track_file: Option<&mut PointTrack>,
 		//
 		span: Span,
 	) -> TreeResult<Self::Result>;
 
+	/// Whether the behavior accepts the syntax kind before the path (`expr: "x.tt"`).
+	const IS_SUPPORT_KIND: bool = false;
+
+	/// Whether the arguments are the path to a file, which is relative to the
+	/// including file inside `[recursive]` ones (not the parts of a name).
+	const IS_PATH: bool = true;
+
+	/// Names of the flags supported by the behavior (`[flag]`).
+	const FLAGS: &'static [&'static str] = &[];
+
+	/// Flags that are also accepted before the paths: `#str(raw, "a.sql")`.
+	const LEADING_FLAGS: &'static [&'static str] = &[];
+
+	/// Names of the options supported by the behavior (`; name = value`).
+	const OPTIONS: &'static [&'static str] = &[];
+
 	/// Create an empty valid tree.
-	fn make_empty_tree(group_span: Span) -> Self::Result;
+	///
+	/// (Behaviors that cannot be empty return an error here)
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result>;
+
+	/// Search for directives in the trees of the included file, `[recursive]`.
+	///
+	/// (Only called for behaviors that list the `recursive` flag)
+	#[allow(unused_variables)]
+	#[inline]
+	fn expand_nested(
+		tree: Self::Result,
+		path: &Path,
+		state: &mut SearchState,
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		TreeResult::Ok(tree)
+	}
+
+	/// The final form of the tree after the trees of all paths are combined.
+	#[allow(unused_variables)]
+	#[inline]
+	fn finish(tree: Self::Result, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(tree)
+	}
+
+	/// Combine the trees of several paths into one, separating them with the `sep` option.
+	///
+	/// (Behaviors that support only one path return an error here)
+	#[allow(unused_variables)]
+	fn join(trees: Vec<Self::Result>, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		throw_sg_err! {
+			return [span]: "This directive accepts only one path."
+		}
+	}
+}
+
+/// Flags choosing the group in which the trees of the file are wrapped,
+/// only one of them can be given.
+const WRAP_FLAGS: &[&str] = &["paren", "brace", "bracket", "flat"];
+
+/// Join the lists of flags in a constant, `N` is the sum of their lengths.
+const fn concat_flags<const N: usize>(a: &[&'static str], b: &[&'static str]) -> [&'static str; N] {
+	let mut result = [""; N];
+	let mut i = 0;
+	while i < a.len() {
+		result[i] = a[i];
+		i += 1;
+	}
+	while i < N {
+		result[i] = b[i - a.len()];
+		i += 1;
+	}
+
+	result
+}
+
+/// Flags of the directives including trees: the group in which the trees
+/// of the file are wrapped, and the search for directives in the file.
+const TT_FLAGS: &[&str] = &concat_flags::<{ WRAP_FLAGS.len() + 1 }>(WRAP_FLAGS, &["recursive"]);
+
+/// Flags of `#tt` and `#words`, the trees ones and those of `normalize_text`.
+const TEXT_TT_FLAGS: &[&str] =
+	&concat_flags::<{ TT_FLAGS.len() + NORMALIZE_FLAGS.len() }>(TT_FLAGS, NORMALIZE_FLAGS);
+
+/// Flags of `#ctt`, `report` lists the constructs changed by the preprocessing
+/// in a warning (so the result must be in item or statement position).
+const CTT_FLAGS: &[&str] = &concat_flags::<{ TEXT_TT_FLAGS.len() + 1 }>(TEXT_TT_FLAGS, &["report"]);
+
+/// Make the item reporting the note as a warning.
+///
+/// (There are no warnings for procedural macros on stable,
+/// the use of a deprecated item is reported instead)
+fn make_note(note: &str, span: Span) -> TokenStream2 {
+	quote_spanned! { span =>
+		const _: () = {
+			#[deprecated(note = #note)]
+			const NOTE: () = ();
+			NOTE
+		};
+	}
+}
+
+/// Wrap the trees of the file in the group selected by the flags,
+/// by default in an invisible group.
+fn wrap_tt(ett: TokenStream2, args: &IncludeArgs, span: Span) -> TokenTree2 {
+	let delimiter = if args.has_flag("flat") {
+		return make_flat_group(ett, span);
+	} else if args.has_flag("paren") {
+		Delimiter::Parenthesis
+	} else if args.has_flag("brace") {
+		Delimiter::Brace
+	} else if args.has_flag("bracket") {
+		Delimiter::Bracket
+	} else {
+		Delimiter::None
+	};
+
+	let mut ngroup = Group::new(delimiter, ett);
+	ngroup.set_span(span);
+
+	TokenTree2::Group(ngroup)
+}
+
+/// Combine the trees of the files wrapped by `wrap_tt`, inserting `sep` between them.
+fn join_wrapped_tt(trees: Vec<TokenTree2>, args: &IncludeArgs, span: Span) -> TokenTree2 {
+	let sep = args.option("sep");
+
+	let mut ett = TokenStream2::new();
+	for (i, tt) in trees.into_iter().enumerate() {
+		if let Some(sep) = sep.filter(|_| i != 0) {
+			ett.extend(sep.clone());
+		}
+		match tt {
+			TokenTree2::Group(group) => match as_flat_group(&group) {
+				Some(stream) => ett.extend(stream),
+				None => ett.extend(group.stream()),
+			},
+			tt => ett.extend([tt]),
+		}
+	}
+
+	wrap_tt(ett, args, span)
+}
+
+/// Search for directives in the trees of the file wrapped by `wrap_tt`,
+/// keeping the wrapper.
+fn expand_nested_tt(
+	tree: TokenTree2,
+	path: &Path,
+	state: &mut SearchState,
+	span: Span,
+) -> TreeResult<TokenTree2> {
+	match tree {
+		TokenTree2::Group(group) => match as_flat_group(&group) {
+			Some(stream) => TreeResult::Ok(make_flat_group(
+				tq!(state.expand_nested(path, stream, span)),
+				group.span(),
+			)),
+			None => {
+				let stream = tq!(state.expand_nested(path, group.stream(), span));
+				let mut ngroup = Group::new(group.delimiter(), stream);
+				ngroup.set_span(group.span());
+
+				TreeResult::Ok(TokenTree2::Group(ngroup))
+			}
+		},
+		tt => TreeResult::Ok(tt),
+	}
+}
+
+/// Get the value of a literal built by the behavior itself.
+fn parse_own_lit<T: syn::parse::Parse>(tt: TokenTree2) -> T {
+	syn::parse2(tt.into()).expect("The behavior created an unexpected literal")
 }
 
 /// Easily include trees from a file in your
@@ -39,13 +236,33 @@ pub enum InjectTT {}
 impl BehMacroInclude for InjectTT {
 	type Result = TokenTree2;
 
+	const IS_SUPPORT_KIND: bool = true;
+	const FLAGS: &'static [&'static str] = TEXT_TT_FLAGS;
+	const OPTIONS: &'static [&'static str] = &["sep"];
+
 	#[inline]
-	fn make_empty_tree(group_span: Span) -> Self::Result {
-		make_null_group(group_span)
+	fn expand_nested(
+		tree: Self::Result,
+		path: &Path,
+		state: &mut SearchState,
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		expand_nested_tt(tree, path, state, span)
+	}
+
+	#[inline]
+	fn join(trees: Vec<Self::Result>, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(join_wrapped_tt(trees, args, span))
+	}
+
+	#[inline]
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(make_null_group(group_span))
 	}
 
 	fn make_tree(
 		sspath: &ExprLit,
+		args: &IncludeArgs,
 		point_track: Option<&mut PointTrack>,
 
 		span: Span,
@@ -54,114 +271,194 @@ impl BehMacroInclude for InjectTT {
 		load_file_and_automake_tree_with_fns(
 			path,
 			point_track,
-			|_| {}, /* skip_prepare */
+			args.kind,
+			|data| *data = normalize_text(core::mem::take(data), |a| args.has_flag(a)),
 			|fs_tt| {
 				let ett = fs_tt.map_or_else(TokenStream2::new, TokenStream2::from_iter);
 
-				let mut ngroup = Group::new(Delimiter::None, ett);
-				ngroup.set_span(span);
+				TreeResult::Ok(wrap_tt(ett, args, span))
+			},
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+		)
+	}
+}
+
+/// Include trees from a file of arbitrary text, never failing on
+/// what the Rust lexer does not accept (see `lex_lenient`).
+pub enum InjectWords {}
 
-				TreeResult::Ok(TokenTree2::Group(ngroup))
+impl BehMacroInclude for InjectWords {
+	type Result = TokenTree2;
+
+	const FLAGS: &'static [&'static str] = TEXT_TT_FLAGS;
+	const OPTIONS: &'static [&'static str] = &["sep"];
+
+	#[inline]
+	fn expand_nested(
+		tree: Self::Result,
+		path: &Path,
+		state: &mut SearchState,
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		expand_nested_tt(tree, path, state, span)
+	}
+
+	#[inline]
+	fn join(trees: Vec<Self::Result>, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(join_wrapped_tt(trees, args, span))
+	}
+
+	#[inline]
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(make_null_group(group_span))
+	}
+
+	fn make_tree(
+		sspath: &ExprLit,
+		args: &IncludeArgs,
+		point_track: Option<&mut PointTrack>,
+
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		let path = Path::new(sspath.as_str());
+		load_file_to_string_with_fns(
+			path,
+			point_track,
+			|data| {
+				let data = normalize_text(data, |a| args.has_flag(a));
+
+				TreeResult::Ok(wrap_tt(lex_lenient(&data), args, span))
 			},
-			|e| TreeResult::Err(e.into_tt_err(span)),
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
 		)
 	}
 }
 
-/// Regular macro `include_tt` with find and replace
-/// invalid tokens breaking the parser.
-///
-/// (Implemented specifically for C-like languages using `\` as a line code string)
+/// Regular macro `include_tt` with the C constructs breaking
+/// the parser changed outside of literals (line continuations,
+/// comments, preprocessor directives, character literals).
 pub enum InjectCTT {}
 
 impl BehMacroInclude for InjectCTT {
 	type Result = TokenTree2;
 
+	const IS_SUPPORT_KIND: bool = true;
+	const FLAGS: &'static [&'static str] = CTT_FLAGS;
+	const OPTIONS: &'static [&'static str] = &["sep"];
+
+	#[inline]
+	fn expand_nested(
+		tree: Self::Result,
+		path: &Path,
+		state: &mut SearchState,
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		expand_nested_tt(tree, path, state, span)
+	}
+
 	#[inline]
-	fn make_empty_tree(group_span: Span) -> Self::Result {
-		make_null_group(group_span)
+	fn join(trees: Vec<Self::Result>, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(join_wrapped_tt(trees, args, span))
+	}
+
+	#[inline]
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(make_null_group(group_span))
 	}
 
 	fn make_tree(
 		sspath: &ExprLit,
+		args: &IncludeArgs,
 		point_track: Option<&mut PointTrack>,
 
 		span: Span,
 	) -> TreeResult<Self::Result> {
 		let sspath = Path::new(sspath);
+		// What the preprocessing changed, for messages.
+		let report = Cell::new(Vec::new());
 		load_file_and_automake_tree_with_fns(
 			sspath,
 			point_track,
+			args.kind,
 			|p_string| {
-				/* fix unk start token */
-				let mut p_str = p_string.as_mut();
-				while let Some(pos) = p_str.find('\\' /* one symb */) {
-					let right = unsafe {
-						debug_assert!(p_str.get(pos..).is_some());
-
-						p_str.get_unchecked_mut(pos..)
+				let data = normalize_text(core::mem::take(p_string), |a| args.has_flag(a));
+				let (data, altered) = prepare_c_source(&data);
+				*p_string = data;
+				report.set(altered);
+			},
+			|fs_tt| {
+				if args.has_flag("report") {
+					let altered = report.take();
+					let salt = match altered.is_empty() {
+						true => String::from("nothing"),
+						false => describe_alterations(&altered),
 					};
+					let note = format!(
+						"The `#ctt` preprocessing of {} altered: {salt}.",
+						sspath.display()
+					);
+					let ett = fs_tt.map_or_else(TokenStream2::new, TokenStream2::from_iter);
+					let mut note = make_note(&note, span);
+					// `flat` trees are spliced along with the note.
+					if args.has_flag("flat") {
+						note.extend(ett);
 
-					if right.len() >= 2 {
-						let (c_symbol, new_pstr) = right.split_at_mut(2);
-						let c_array = unsafe { c_symbol.as_bytes_mut() };
-						debug_assert_eq!(c_array.len(), 2);
-						debug_assert_eq!(
-							{
-								#[allow(clippy::get_first)]
-								// why?, this is done to be completely analogous to an unsafe function.
-								c_array.get(0)
-							},
-							Some(&b'\\')
-						);
-						debug_assert!(c_array.get(1).is_some());
-
-						match unsafe { c_array.get_unchecked(1) } {
-							b'\n' | b'\t' | b'\r' | b' ' => {
-								// This is generally safe as the '/'
-								// characters were found using utf-8 lookups.
-								let a_repl = unsafe { c_array.get_unchecked_mut(0) };
-								*a_repl = b' ';
-							}
-							/*b'\\' => {
-								// This is generally safe as the '/'
-								// characters were found using utf-8 lookups.
-								for a in c_array {
-									*a = b' ';
-								}
-							},*/
-							_ => {}
-						}
+						return TreeResult::Ok(make_flat_group(note, span));
+					}
+					note.extend([wrap_tt(ett, args, span)]);
 
-						if new_pstr.is_empty() {
-							break;
-						}
-						p_str = new_pstr;
-					} else {
-						// This is generally safe as the '/'
-						// characters were found using utf-8 lookups.
-						let array = unsafe { right.as_bytes_mut() }.iter_mut();
-						for a in array {
-							*a = b' ';
-						}
+					let mut ngroup = Group::new(Delimiter::None, note);
+					ngroup.set_span(span);
 
-						break;
-					}
+					return TreeResult::Ok(TokenTree2::Group(ngroup));
 				}
-			},
-			|fs_tt| {
 				let ett = fs_tt.map_or_else(TokenStream2::new, TokenStream2::from_iter);
 
-				let mut ngroup = Group::new(Delimiter::None, ett);
-				ngroup.set_span(span);
+				TreeResult::Ok(wrap_tt(ett, args, span))
+			},
+			|e| {
+				let mut err = e.into_tt_err(args.err_span(span));
+				let altered = report.take();
+				if !altered.is_empty() {
+					let salt = describe_alterations(&altered);
+					err.extend::<TokenStream2>(throw_sg_err! {
+						[span]: "The `#ctt` preprocessing altered: ", #salt, "."
+					});
+				}
 
-				TreeResult::Ok(TokenTree2::Group(ngroup))
+				TreeResult::Err(err)
 			},
-			|e| TreeResult::Err(e.into_tt_err(span)),
 		)
 	}
 }
 
+/// Flags of `#str`, `raw` and those of `normalize_text`.
+const STR_FLAGS: &[&str] =
+	&concat_flags::<{ 1 + NORMALIZE_FLAGS.len() }>(&["raw"], NORMALIZE_FLAGS);
+
+/// Make the string literal, `r#"..."#` with the fewest `#` if the flag
+/// `raw` is set (and the text has no `\r`, which raw strings cannot contain).
+fn make_str_lit(data: &str, args: &IncludeArgs, span: Span) -> TokenTree2 {
+	let raw = match args.has_flag("raw") && !data.contains('\r') {
+		true => {
+			// One more `#` than the longest `"#..#` in the text.
+			let hashes = data
+				.split('"')
+				.skip(1)
+				.map(|a| a.len() - a.trim_start_matches('#').len() + 1)
+				.max()
+				.unwrap_or(0);
+			let hashes = "#".repeat(hashes);
+			Literal::from_str(&format!("r{hashes}\"{data}\"{hashes}")).ok()
+		}
+		false => None,
+	};
+	let mut lit = raw.unwrap_or_else(|| Literal::string(data));
+	lit.set_span(span);
+
+	TokenTree2::Literal(lit)
+}
+
 /// Includes the entire file as a single line,
 /// similar to 'include_str'.
 pub enum InjectStr {}
@@ -169,117 +466,845 @@ pub enum InjectStr {}
 impl BehMacroInclude for InjectStr {
 	type Result = TokenTree2;
 
-	fn make_empty_tree(group_span: Span) -> Self::Result {
+	const FLAGS: &'static [&'static str] = STR_FLAGS;
+	const LEADING_FLAGS: &'static [&'static str] = &["raw"];
+	const OPTIONS: &'static [&'static str] = &["sep", "encoding"];
+
+	fn join(trees: Vec<Self::Result>, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		let sep = args.option("sep");
+		let sep = match sep.map(|a| syn::parse2::<LitStr>(a.clone())) {
+			Some(Ok(a)) => a.value(),
+			Some(Err(e)) => throw_sg_err! {
+				return [e.span()]: "`sep` must be a string literal: `sep = \"\\n\"`."
+			},
+			None => String::new(),
+		};
+		let data = trees
+			.into_iter()
+			.map(|tt| parse_own_lit::<LitStr>(tt).value())
+			.collect::<Vec<_>>()
+			.join(&sep);
+
+		TreeResult::Ok(make_str_lit(&data, args, span))
+	}
+
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
 		let mut lit = Literal::string("");
 		lit.set_span(group_span);
 
-		TokenTree2::Literal(lit)
+		TreeResult::Ok(TokenTree2::Literal(lit))
 	}
 
 	fn make_tree(
 		sspath: &ExprLit,
+		args: &IncludeArgs,
 		point_track: Option<&mut PointTrack>,
 
 		span: Span,
 	) -> TreeResult<Self::Result> {
-		let path = Path::new(sspath);
-
-		match std::fs::read_to_string(path) {
-			Ok(data) => {
-				if let Some(point_track) = point_track {
-					point_track.append_track_file(path);
-				}
-				let mut lit = Literal::string(&data);
-				lit.set_span(span);
+		let encoding = match args.option("encoding") {
+			Some(value) => match syn::parse2::<LitStr>(value.clone()) {
+				Ok(name) => match Encoding::from_name(&name.value()) {
+					Some(a) => Some(a),
+					None => {
+						let names = Encoding::NAMES;
+						throw_sg_err! {
+							return [name.span()]: "Unknown encoding, expected: ", #names, "."
+						}
+					}
+				},
+				Err(e) => throw_sg_err! {
+					return [e.span()]: "`encoding` must be a string literal: `encoding = \"utf-16le\"`."
+				},
+			},
+			None => None,
+		};
 
-				TreeResult::Ok(TokenTree2::Literal(lit))
-			}
-			Err(e) => {
-				let path = path
-					.canonicalize()
-					.map_or_else(|_| Cow::Borrowed(path), Cow::Owned);
+		let path = Path::new(sspath);
+		load_file_to_bytes_with_fns(
+			path,
+			point_track,
+			|data| {
+				let data = match decode_text(&data, encoding) {
+					Ok(a) => a,
+					Err(msg) => {
+						return TreeResult::Err(
+							LoadFileAndAutoMakeTreeErr::invalid_content(msg, Cow::Borrowed(path))
+								.into_tt_err(args.err_span(span)),
+						);
+					}
+				};
+				let data = normalize_text(data, |a| args.has_flag(a));
 
-				TreeResult::Err(
-					LoadFileAndAutoMakeTreeErr::read_to_string(e, path).into_tt_err(span),
-				)
-			}
-		}
+				TreeResult::Ok(make_str_lit(&data, args, span))
+			},
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+		)
 	}
 }
 
 /// Includes the entire file as a binary array,
 /// similar to 'include_str'.
+///
+/// (`shape = array | slice | str` changes the type, `static = NAME`
+/// declares a `static` item with the data)
 pub enum InjectArr {}
 
 impl BehMacroInclude for InjectArr {
 	type Result = TokenTree2;
 
-	fn make_empty_tree(group_span: Span) -> Self::Result {
+	const OPTIONS: &'static [&'static str] = &["sep", "shape", "static"];
+
+	fn finish(tree: Self::Result, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		let shape = match args.option("shape").map(|a| Vec::from_iter(a.clone())) {
+			None => String::from("bytes"),
+			Some(value) => match value.as_slice() {
+				[TokenTree2::Ident(name)] => name.to_string(),
+				_ => throw_sg_err! {
+					return [span]: "`shape` must be one of: `bytes`, `array`, `slice`, `str`."
+				},
+			},
+		};
+		let decl = args.option("static").map(|a| Vec::from_iter(a.clone()));
+		// The byte string stays a literal (`$a:literal`, patterns).
+		if shape == "bytes" && decl.is_none() {
+			return TreeResult::Ok(tree);
+		}
+		let data = parse_own_lit::<LitByteStr>(tree.clone()).value();
+		let len = Literal::usize_unsuffixed(data.len());
+		let (ty, value) = match shape.as_str() {
+			"bytes" => (quote!(&[u8; #len]), quote!(#tree)),
+			"array" => (quote!([u8; #len]), quote!(*#tree)),
+			"slice" => (quote!(&[u8]), quote!(#tree as &[u8])),
+			"str" => match String::from_utf8(data) {
+				Ok(a) => {
+					let mut lit = Literal::string(&a);
+					lit.set_span(span);
+					(quote!(&str), quote!(#lit))
+				}
+				Err(e) => {
+					let pos = e.utf8_error().valid_up_to().to_string();
+					throw_sg_err! {
+						return [span]: "The data is not valid UTF-8 (byte ", #pos, "), it cannot be included as `&str`."
+					}
+				}
+			},
+			_ => throw_sg_err! {
+				return [span]: "Unknown shape, expected: `bytes` (`&[u8; N]`), `array` (`[u8; N]`), `slice` (`&[u8]`), `str` (`&str`)."
+			},
+		};
+
+		let stream = match decl {
+			// `static = pub NAME`
+			Some(decl) => match decl.as_slice() {
+				[vis @ .., TokenTree2::Ident(name)] => {
+					let vis = TokenStream2::from_iter(vis.iter().cloned());
+					quote_spanned!(span=> #vis static #name: #ty = #value;)
+				}
+				_ => throw_sg_err! {
+					return [span]: "The name of the item was expected: `static = NAME`."
+				},
+			},
+			None => value,
+		};
+		let mut ngroup = Group::new(Delimiter::None, stream);
+		ngroup.set_span(span);
+
+		TreeResult::Ok(TokenTree2::Group(ngroup))
+	}
+
+	fn join(trees: Vec<Self::Result>, args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		let sep = args.option("sep");
+		let sep = match sep.map(|a| syn::parse2::<Lit>(a.clone())) {
+			Some(Ok(Lit::ByteStr(a))) => a.value(),
+			Some(Ok(Lit::Str(a))) => a.value().into_bytes(),
+			Some(Ok(Lit::Byte(a))) => vec![a.value()],
+			_ if sep.is_none() => Vec::new(),
+			_ => throw_sg_err! {
+				return [span]: "`sep` must be a byte string, string or byte literal: `sep = b\"\\0\"`."
+			},
+		};
+		let data = trees
+			.into_iter()
+			.map(|tt| parse_own_lit::<LitByteStr>(tt).value())
+			.collect::<Vec<_>>()
+			.join(sep.as_slice());
+
+		let mut lit = Literal::byte_string(&data);
+		lit.set_span(span);
+
+		TreeResult::Ok(TokenTree2::Literal(lit))
+	}
+
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
 		let mut lit = Literal::byte_string(&[]);
 		lit.set_span(group_span);
 
-		TokenTree2::Literal(lit)
+		TreeResult::Ok(TokenTree2::Literal(lit))
+	}
+
+	fn make_tree(
+		sspath: &ExprLit,
+		args: &IncludeArgs,
+		point_track: Option<&mut PointTrack>,
+
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		let path = Path::new(sspath);
+		load_file_to_bytes_with_fns(
+			path,
+			point_track,
+			|data| {
+				let mut lit = Literal::byte_string(&data);
+				lit.set_span(span);
+
+				TreeResult::Ok(TokenTree2::Literal(lit))
+			},
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+		)
+	}
+}
+
+/// Constants of a dotenv file: `KEY=VALUE` becomes `pub const KEY: &str`,
+/// `KEY:u16=VALUE` is a constant of the given type.
+///
+/// (The names are not changed, lowercase keys are allowed as constants)
+pub enum InjectKv {}
+
+impl BehMacroInclude for InjectKv {
+	type Result = TokenTree2;
+
+	fn join(trees: Vec<Self::Result>, _args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		let mut ngroup = Group::new(Delimiter::None, TokenStream2::from_iter(trees));
+		ngroup.set_span(span);
+
+		TreeResult::Ok(TokenTree2::Group(ngroup))
+	}
+
+	#[inline]
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(make_null_group(group_span))
 	}
 
 	fn make_tree(
 		sspath: &ExprLit,
+		args: &IncludeArgs,
 		point_track: Option<&mut PointTrack>,
 
 		span: Span,
 	) -> TreeResult<Self::Result> {
 		let path = Path::new(sspath);
-		let vec = {
-			let make_err = |e: IOError| {
-				let path = path
-					.canonicalize()
-					.map_or_else(|_| Cow::Borrowed(path), Cow::Owned);
-				TreeResult::from(
-					LoadFileAndAutoMakeTreeErr::read_to_string(e, path).into_tt_err(span),
+		let data = tq!(load_file_to_string_with_fns(
+			path,
+			point_track,
+			TreeResult::Ok,
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+		));
+		let invalid = |line: usize, msg: String| {
+			TreeResult::Err(
+				LoadFileAndAutoMakeTreeErr::invalid_content(
+					format!("line {line}: {msg}"),
+					Cow::Borrowed(path),
 				)
-			};
-			let mut file = match File::open(path) {
+				.into_tt_err(args.err_span(span)),
+			)
+		};
+		let entries = match parse_dotenv(&data) {
+			Ok(a) => a,
+			Err(e) => return invalid(e.line, e.msg),
+		};
+
+		let mut ett = TokenStream2::new();
+		for a in entries {
+			let name = match make_ident(&a.key, span) {
 				Ok(a) => a,
-				Err(e) => return make_err(e),
+				Err(..) => {
+					return invalid(
+						a.line,
+						format!("`{}` is not a valid Rust identifier", a.key),
+					);
+				}
+			};
+			let (ty, value) = match a.ty.as_deref() {
+				None | Some("str") => {
+					let mut lit = Literal::string(&a.value);
+					lit.set_span(span);
+					(quote!(&str), TokenTree2::Literal(lit))
+				}
+				Some(ty) => match parse_scalar_by_name(ty, a.value.trim()) {
+					Some(Ok(mut value)) => {
+						value.set_span(span);
+						let ty = Ident::new(ty, span);
+						(quote!(#ty), value)
+					}
+					Some(Err(e)) => return invalid(a.line, e),
+					None => {
+						return invalid(
+							a.line,
+							format!(
+								"unknown type `{ty}`, expected `str`, `bool`, `char`, `u8`..`u128`, `i8`..`i128`, `usize`, `isize`, `f32`, `f64`"
+							),
+						);
+					}
+				},
 			};
+			// The keys keep their case, `host=..` is `pub const host`.
+			ett.extend(quote! {
+				#[allow(non_upper_case_globals)]
+				pub const #name: #ty = #value;
+			});
+		}
+
+		let mut ngroup = Group::new(Delimiter::None, ett);
+		ngroup.set_span(span);
+
+		TreeResult::Ok(TokenTree2::Group(ngroup))
+	}
+}
+
+/// Constants of a C header: `#define NAME value` and `enum { ... }` become
+/// `pub const` items, the definitions that cannot be translated are skipped
+/// with a warning (`[quiet]` disables it).
+///
+/// (Integers keep their C type, `long` is 64 bits unless `long = 32` is given
+/// for Windows and 32-bit targets)
+pub enum InjectCDefines {}
+
+impl BehMacroInclude for InjectCDefines {
+	type Result = TokenTree2;
+
+	const FLAGS: &'static [&'static str] = &["quiet"];
+	const OPTIONS: &'static [&'static str] = &["long"];
+
+	fn join(trees: Vec<Self::Result>, _args: &IncludeArgs, span: Span) -> TreeResult<Self::Result> {
+		let mut ngroup = Group::new(Delimiter::None, TokenStream2::from_iter(trees));
+		ngroup.set_span(span);
+
+		TreeResult::Ok(TokenTree2::Group(ngroup))
+	}
+
+	#[inline]
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(make_null_group(group_span))
+	}
+
+	fn make_tree(
+		sspath: &ExprLit,
+		args: &IncludeArgs,
+		point_track: Option<&mut PointTrack>,
+
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		let long = match args.option("long").map(|a| a.to_string()) {
+			None => 64,
+			Some(a) if a == "32" => 32,
+			Some(a) if a == "64" => 64,
+			Some(..) => throw_sg_err! {
+				return [span]: "`long` must be `32` or `64`: `long = 32`."
+			},
+		};
+
+		let path = Path::new(sspath);
+		let data = tq!(load_file_to_string_with_fns(
+			path,
+			point_track,
+			TreeResult::Ok,
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+		));
+		let header = parse_c_header(&data, long);
 
-			let mut vec = Vec::new(); // capacity is not required.
-			if let Err(e) = file.read_to_end(&mut vec) {
-				return make_err(e);
+		let mut ett = TokenStream2::new();
+		let mut skipped = header.skipped;
+		for c in header.consts {
+			let result = match make_ident(&c.name, span) {
+				Ok(name) => c.value.to_tokens(span).map(|a| (name, a)),
+				Err(..) => Err("the name is not a valid Rust identifier".into()),
 			};
+			match result {
+				Ok((name, (ty, value))) => ett.extend(quote! {
+					pub const #name: #ty = #value;
+				}),
+				Err(reason) => skipped.push(CSkipped {
+					line: c.line,
+					name: c.name,
+					reason,
+				}),
+			}
+		}
+
+		if !args.has_flag("quiet") {
+			skipped.sort_by_key(|a| a.line);
+			for a in skipped {
+				let note = format!(
+					"`{}` in {}, line {}, was skipped: {}",
+					a.name,
+					path.display(),
+					a.line,
+					a.reason
+				);
+				ett.extend(make_note(&note, span));
+			}
+		}
+
+		let mut ngroup = Group::new(Delimiter::None, ett);
+		ngroup.set_span(span);
+
+		TreeResult::Ok(TokenTree2::Group(ngroup))
+	}
+}
+
+/// Embeds every file of the directory (recursively) as a web asset record
+/// `(path, bytes, mime, etag)`, where `path` is relative to the directory
+/// and uses `/` as a separator.
+///
+/// (Symlinks to directories are skipped, so that a cycle does not recurse forever.
+/// Only the found files are tracked, adding or removing a file in the directory
+/// requires a rebuild)
+pub enum InjectAssets {}
+
+impl InjectAssets {
+	/// Wrap the records in a slice with an explicit type,
+	/// so that byte strings of different lengths can coexist.
+	fn make_slice(records: TokenStream2, span: Span) -> TokenTree2 {
+		let mut ngroup = Group::new(
+			Delimiter::Brace,
+			quote! {
+				const ASSETS: &[(&str, &[u8], &str, &str)] = &[#records];
+				ASSETS
+			},
+		);
+		ngroup.set_span(span);
+
+		TokenTree2::Group(ngroup)
+	}
+
+	/// Collect all files of the directory, including nested ones
+	/// (symlinks to directories are skipped).
+	fn collect_files(
+		dir: &Path,
+		files: &mut Vec<PathBuf>,
+	) -> Result<(), LoadFileAndAutoMakeTreeErr<'static>> {
+		let make_err = |e| LoadFileAndAutoMakeTreeErr::read_dir(e, Cow::Owned(dir.to_path_buf()));
+		for entry in std::fs::read_dir(dir).map_err(make_err)? {
+			let entry = entry.map_err(make_err)?;
+			let file_type = entry.file_type().map_err(make_err)?;
+			let path = entry.path();
+			if file_type.is_dir() {
+				Self::collect_files(&path, files)?;
+			} else if !file_type.is_symlink() || !path.is_dir() {
+				files.push(path);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl BehMacroInclude for InjectAssets {
+	type Result = TokenTree2;
+
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		TreeResult::Ok(Self::make_slice(TokenStream2::new(), group_span))
+	}
 
-			vec
+	fn make_tree(
+		sspath: &ExprLit,
+		args: &IncludeArgs,
+		mut point_track: Option<&mut PointTrack>,
+
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		let dir = Path::new(sspath);
+		let make_err = |e: IOError, path: &Path| {
+			let path = path
+				.canonicalize()
+				.map_or_else(|_| Cow::Borrowed(path), Cow::Owned);
+			TreeResult::from(
+				LoadFileAndAutoMakeTreeErr::read_to_string(e, path)
+					.into_tt_err(args.err_span(span)),
+			)
 		};
 
-		if let Some(point_track) = point_track {
-			point_track.append_track_file(path);
+		let mut files = Vec::new();
+		if let Err(e) = Self::collect_files(dir, &mut files) {
+			return TreeResult::Err(e.into_tt_err(args.err_span(span)));
 		}
-		let mut lit = Literal::byte_string(&vec);
-		lit.set_span(span);
+		// The order of `read_dir` is platform dependent.
+		files.sort();
 
-		TreeResult::Ok(TokenTree2::Literal(lit))
+		let mut records = TokenStream2::new();
+		for file in files {
+			let data = match std::fs::read(&file) {
+				Ok(a) => a,
+				Err(e) => return make_err(e, &file),
+			};
+			track_file(point_track.as_deref_mut(), &file);
+
+			let rel_path = file
+				.strip_prefix(dir)
+				.unwrap_or(&file)
+				.components()
+				.map(|a| a.as_os_str().to_string_lossy())
+				.collect::<Vec<_>>()
+				.join("/");
+			let mime = guess_mime(&file);
+			let etag = make_etag(&data);
+			let bytes = Literal::byte_string(&data);
+
+			records.extend(quote! {
+				(#rel_path, #bytes, #mime, #etag),
+			});
+		}
+
+		TreeResult::Ok(Self::make_slice(records, span))
+	}
+}
+
+/// Builds a single identifier from the concatenated parts,
+/// `r#` at the beginning creates a raw identifier.
+pub enum InjectIdent {}
+
+impl BehMacroInclude for InjectIdent {
+	type Result = TokenTree2;
+
+	const IS_PATH: bool = false;
+
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		throw_sg_err! {
+			return [group_span]: "An identifier cannot be empty."
+		}
+	}
+
+	fn make_tree(
+		name: &ExprLit,
+		args: &IncludeArgs,
+		_point_track: Option<&mut PointTrack>,
+
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		match make_ident(name, span) {
+			Ok(ident) => TreeResult::Ok(TokenTree2::Ident(ident)),
+			Err(e) => {
+				let err_span = args.err_span(span);
+				TreeResult::Err(err_span.apply(e.into_tt_err(err_span.first)))
+			}
+		}
+	}
+}
+
+/// Includes a file that must contain exactly one literal,
+/// unlike `InjectTT` anything else is an error.
+pub enum InjectLit {}
+
+impl BehMacroInclude for InjectLit {
+	type Result = TokenTree2;
+
+	fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+		throw_sg_err! {
+			return [group_span]: "The path to the file with the literal was expected."
+		}
+	}
+
+	fn make_tree(
+		sspath: &ExprLit,
+		args: &IncludeArgs,
+		point_track: Option<&mut PointTrack>,
+
+		span: Span,
+	) -> TreeResult<Self::Result> {
+		let path = Path::new(sspath.as_str());
+		load_file_and_automake_tree_with_fns(
+			path,
+			point_track,
+			SyntaxKind::TokenTrees,
+			|_| {}, /* skip_prepare */
+			|fs_tt| match expect_one_literal(fs_tt.unwrap_or_default()) {
+				Ok(ett) => {
+					let mut ngroup = Group::new(Delimiter::None, ett);
+					ngroup.set_span(span);
+
+					TreeResult::Ok(TokenTree2::Group(ngroup))
+				}
+				Err(msg) => TreeResult::Err(
+					LoadFileAndAutoMakeTreeErr::invalid_content(msg, Cow::Borrowed(path))
+						.into_tt_err(args.err_span(span)),
+				),
+			},
+			|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+		)
 	}
 }
 
+/// Declares behaviors that read a scalar value of the given type from a file
+/// with strict validation and build a suffixed literal.
+macro_rules! inject_scalar {
+	[ $( $t:ident: $name:ident; )* ] => {
+		$(
+			#[doc = concat!("Includes a file containing a single `", stringify!($t), "` value.")]
+			pub enum $name {}
+
+			impl BehMacroInclude for $name {
+				type Result = TokenTree2;
+
+				#[inline]
+				fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+					InjectLit::make_empty_tree(group_span)
+				}
+
+				fn make_tree(
+					sspath: &ExprLit,
+					args: &IncludeArgs,
+					point_track: Option<&mut PointTrack>,
+
+					span: Span,
+				) -> TreeResult<Self::Result> {
+					let path = Path::new(sspath);
+					load_file_to_string_with_fns(
+						path,
+						point_track,
+						|data| match <$t as ParseScalar>::parse_scalar(data.trim()) {
+							Ok(mut tt) => {
+								tt.set_span(span);
+
+								TreeResult::Ok(tt)
+							}
+							Err(msg) => TreeResult::Err(
+								LoadFileAndAutoMakeTreeErr::invalid_content(msg, Cow::Borrowed(path))
+									.into_tt_err(args.err_span(span)),
+							),
+						},
+						|e| TreeResult::Err(e.into_tt_err(args.err_span(span))),
+					)
+				}
+			}
+		)*
+	};
+}
+
+inject_scalar! {
+	u8: InjectU8;
+	u16: InjectU16;
+	u32: InjectU32;
+	u64: InjectU64;
+	u128: InjectU128;
+	usize: InjectUsize;
+	i8: InjectI8;
+	i16: InjectI16;
+	i32: InjectI32;
+	i64: InjectI64;
+	i128: InjectI128;
+	isize: InjectIsize;
+	f32: InjectF32;
+	f64: InjectF64;
+	bool: InjectBool;
+	char: InjectChar;
+}
+
+/// Declares behaviors that convert the concatenated parts to the selected case
+/// and return them as an identifier (`#snake`) or as a string (`#snake_str`).
+macro_rules! inject_case {
+	[ $( $case:ident: $name:ident, $name_str:ident; )* ] => {
+		$(
+			#[doc = concat!("Converts the concatenated parts to `Case::", stringify!($case), "` and builds an identifier.")]
+			pub enum $name {}
+
+			impl BehMacroInclude for $name {
+				type Result = TokenTree2;
+
+				const IS_PATH: bool = false;
+
+				#[inline]
+				fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+					InjectIdent::make_empty_tree(group_span)
+				}
+
+				fn make_tree(
+					name: &ExprLit,
+					args: &IncludeArgs,
+					point_track: Option<&mut PointTrack>,
+
+					span: Span,
+				) -> TreeResult<Self::Result> {
+					let name = Case::$case.convert(name);
+					let name = unsafe { ExprLit::new_unchecked(&name) };
+
+					InjectIdent::make_tree(name, args, point_track, span)
+				}
+			}
+
+			#[doc = concat!("Converts the concatenated parts to `Case::", stringify!($case), "` and builds a string.")]
+			pub enum $name_str {}
+
+			impl BehMacroInclude for $name_str {
+				type Result = TokenTree2;
+
+				const IS_PATH: bool = false;
+
+				#[inline]
+				fn make_empty_tree(group_span: Span) -> TreeResult<Self::Result> {
+					InjectStr::make_empty_tree(group_span)
+				}
+
+				fn make_tree(
+					name: &ExprLit,
+					_args: &IncludeArgs,
+					_point_track: Option<&mut PointTrack>,
+
+					span: Span,
+				) -> TreeResult<Self::Result> {
+					let mut lit = Literal::string(&Case::$case.convert(name));
+					lit.set_span(span);
+
+					TreeResult::Ok(TokenTree2::Literal(lit))
+				}
+			}
+		)*
+	};
+}
+
+/// Directives whose result is an identifier: `compile_error! { .. }` cannot take
+/// the place of a failed one (see `DirectiveErrs::detach`).
+pub const IDENT_DIRECTIVES: &[&str] = &["ident", "snake", "camel", "pascal", "shouty"];
+
+inject_case! {
+	Snake: InjectSnake, InjectSnakeStr;
+	Camel: InjectCamel, InjectCamelStr;
+	Pascal: InjectPascal, InjectPascalStr;
+	Shouty: InjectShouty, InjectShoutyStr;
+}
+
 /// Build macro `include`/`include_str`/`include_arr`.
 pub fn macro_rule_include<A>(
 	group: &'_ Group,
-	point_track: Option<&mut PointTrack>,
+	flags: Option<&'_ Group>,
+	state: &mut SearchState,
 ) -> TreeResult<A::Result>
 where
 	A: BehMacroInclude,
 {
 	let span = group.span();
-	let stream = group.into_token_stream();
-	stream_stringify_with_fns(
-		stream,
-		|stringify| {
-			let exprlit = unsafe { ExprLit::new_unchecked(&stringify) };
+	let (mut args, mut paths) = tq!(IncludeArgs::parse(flags, group.stream()));
+	// Some flags are also accepted before the paths: `#str(raw, "a.sql")`.
+	while paths.len() > 1 {
+		let mut iter = paths[0].clone().into_iter();
+		let flag = match (iter.next(), iter.next()) {
+			(Some(TokenTree2::Ident(flag)), None) => flag,
+			_ => break,
+		};
+		if A::LEADING_FLAGS.iter().any(|a| flag == a) {
+			args.flags.push(flag);
+			paths.remove(0);
+		} else if A::FLAGS.iter().any(|a| flag == a) {
+			// Otherwise it would be the name of a file.
+			let sflag = flag.to_string();
+			throw_sg_err! {
+				return [flag.span()]: "The flag `", #sflag, "` is written in brackets before the paths: `[", #sflag, "](\"a.txt\")`."
+			}
+		} else {
+			break;
+		}
+	}
+	if !A::IS_SUPPORT_KIND && args.kind != SyntaxKind::TokenTrees {
+		throw_sg_err! {
+			return [span]: "This directive does not support the syntax kind before the path (`expr:`, `items:`, ...), it is only available for `#tt` and `#ctt`."
+		}
+	}
+	for flag in &args.flags {
+		if !A::FLAGS.iter().any(|a| flag == a) {
+			let sflag = flag.to_string();
+			if A::FLAGS.is_empty() {
+				throw_sg_err! {
+					return [flag.span()]: "Unknown flag `", #sflag, "`, this directive has no flags."
+				}
+			}
 
-			A::make_tree(exprlit, point_track, span)
-		},
-		// Empty
-		|| TreeResult::Ok(A::make_empty_tree(span)),
-		// Err
-		TreeResult::Err,
-	)
+			let flags = A::FLAGS.join("`, `");
+			throw_sg_err! {
+				return [flag.span()]: "Unknown flag `", #sflag, "`, expected: `", #flags, "`."
+			}
+		}
+	}
+	let mut wrap_flags = args
+		.flags
+		.iter()
+		.filter(|a| WRAP_FLAGS.iter().any(|w| a == w));
+	if let Some(first) = wrap_flags.next() {
+		if let Some(second) = wrap_flags.find(|a| *a != first) {
+			let sfirst = first.to_string();
+			let ssecond = second.to_string();
+			throw_sg_err! {
+				return [second.span()]: "The flags `", #sfirst, "` and `", #ssecond, "` conflict, the trees can be wrapped in only one way."
+			}
+		}
+	}
+	for (name, _) in &args.options {
+		if !A::OPTIONS.iter().any(|a| name == a) {
+			let sname = name.to_string();
+			if A::OPTIONS.is_empty() {
+				throw_sg_err! {
+					return [name.span()]: "Unknown option `", #sname, "`, this directive has no options."
+				}
+			}
+
+			let options = A::OPTIONS.join("`, `");
+			throw_sg_err! {
+				return [name.span()]: "Unknown option `", #sname, "`, expected: `", #options, "`."
+			}
+		}
+	}
+
+	let mut trees = Vec::with_capacity(paths.len());
+	for path in paths {
+		args.path_span = path_span(&path);
+		trees.push(tq!(stream_stringify_with_fns(
+			path,
+			|stringify| {
+				let stringify = match A::IS_PATH {
+					true => state.chain.resolve(&stringify),
+					false => Cow::Borrowed(stringify.as_str()),
+				};
+				let exprlit = unsafe { ExprLit::new_unchecked(&stringify) };
+
+				let tree = tq!(A::make_tree(
+					exprlit,
+					&args,
+					state.point_track_file.as_mut(),
+					span
+				));
+				match args.has_flag("recursive") {
+					true => A::expand_nested(tree, Path::new(exprlit.as_str()), state, span),
+					false => TreeResult::Ok(tree),
+				}
+			},
+			// Empty
+			|| A::make_empty_tree(span),
+			// Err
+			TreeResult::Err,
+		)));
+	}
+
+	let tree = match trees.len() {
+		1 => trees.remove(0),
+		_ => tq!(A::join(trees, &args, span)),
+	};
+	A::finish(tree, &args, span)
+}
+
+#[cfg(all(test, unix))]
+#[test]
+fn test_assets_symlink_cycle() {
+	let dir = std::env::temp_dir().join(format!("include_tt_assets_{}", std::process::id()));
+	let _ = std::fs::remove_dir_all(&dir);
+	std::fs::create_dir_all(dir.join("a")).unwrap();
+	std::fs::write(dir.join("a/f.txt"), "f").unwrap();
+	std::os::unix::fs::symlink(&dir, dir.join("a/loop")).unwrap();
+	std::os::unix::fs::symlink(dir.join("a/f.txt"), dir.join("link.txt")).unwrap();
+
+	let mut files = Vec::new();
+	let result = InjectAssets::collect_files(&dir, &mut files);
+	files.sort();
+	let _ = std::fs::remove_dir_all(&dir);
+
+	assert!(result.is_ok());
+	assert_eq!(files, [dir.join("a/f.txt"), dir.join("link.txt")]);
 }
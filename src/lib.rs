@@ -57,14 +57,34 @@ assert_eq!(buf, "Welcome, Ferris. Your score is 100500!");
 
 // #![no_std] TODO, impossible without: [std::io::Error, std::{io::Read, fs::File}, std::fs::read_to_string]
 #![allow(clippy::tabs_in_doc_comments)]
+#![cfg_attr(
+	feature = "nightly",
+	feature(proc_macro_tracked_path, proc_macro_tracked_env)
+)]
 
 extern crate alloc;
 extern crate proc_macro;
 
+use crate::chain::IncludeChain;
+use crate::cond::eval_condition;
+use crate::exprs::args::is_punct;
+use crate::suggest::similar;
+use crate::template::{ForEach, macro_rule_for_each};
+use crate::trees::flat::{make_flat_group, make_removed_group};
+use crate::trees::inline_err::DirectiveErrs;
 use crate::trees::null::make_null_group;
 use crate::trees::throw_sg_err;
+use crate::trees::tracker::place_trackers;
+use crate::vars::{RESERVED_NAMES, Vars};
 use crate::{
-	include::{InjectArr, InjectCTT, InjectStr, InjectTT, macro_rule_include},
+	include::{
+		IDENT_DIRECTIVES, InjectArr, InjectAssets, InjectBool, InjectCDefines, InjectCTT,
+		InjectCamel, InjectCamelStr, InjectChar, InjectF32, InjectF64, InjectI8, InjectI16,
+		InjectI32, InjectI64, InjectI128, InjectIdent, InjectIsize, InjectKv, InjectLit,
+		InjectPascal, InjectPascalStr, InjectShouty, InjectShoutyStr, InjectSnake, InjectSnakeStr,
+		InjectStr, InjectTT, InjectU8, InjectU16, InjectU32, InjectU64, InjectU128, InjectUsize,
+		InjectWords, macro_rule_include,
+	},
 	trees::{
 		replace::{replace_tree_in_group, replace_tree_in_stream},
 		result::TreeResult,
@@ -72,19 +92,22 @@ use crate::{
 		tq,
 	},
 };
-use core::slice::IterMut;
+use core::{iter::Peekable, slice::IterMut};
 use proc_macro::TokenStream;
 use proc_macro2::{Delimiter, Group, Span, TokenStream as TokenStream2, TokenTree as TokenTree2};
-use quote::{format_ident, quote};
+use quote::quote;
 use std::path::Path;
 
 /// Components, templates, code for the search
 /// and final construction of trees.
 pub(crate) mod trees {
+	pub mod flat;
 	pub mod group;
+	pub mod inline_err;
 	pub mod null;
 	pub mod replace;
 	pub mod search;
+	pub mod tracker;
 
 	#[macro_use]
 	pub mod result;
@@ -100,24 +123,59 @@ pub(crate) mod trees {
 
 /// Separate syntactic expressions of trees.
 pub(crate) mod exprs {
+	pub mod args;
+	pub mod ident;
+	pub mod kind;
 	pub mod literal;
 }
 
+/// Data formats and tables used to describe the included files.
+pub(crate) mod formats {
+	pub mod c_header;
+	pub mod c_source;
+	pub mod case;
+	pub mod csv;
+	pub mod dotenv;
+	pub mod encoding;
+	pub mod hash;
+	pub mod lenient;
+	pub mod mime;
+	pub mod normalize;
+	pub mod scalar;
+}
+
 /// Code component of macros.
 pub(crate) mod include;
 
+/// Repetition of token templates driven by data files.
+pub(crate) mod template;
+
+/// Macro-local variables, `#let NAME = value;`.
+pub(crate) mod vars;
+
+/// Conditions of `#if COND { ... } #else { ... }` blocks.
+pub(crate) mod cond;
+
+/// Recursive inclusion of files, `#tt[recursive]("a.tt")`.
+pub(crate) mod chain;
+
+/// Suggestions for misspelled names of directives and files.
+pub(crate) mod suggest;
+
 pub(crate) struct PointTrack<'tk> {
 	prefix_token: &'tk mut TokenTree2,
 	name_token: &'tk mut TokenTree2,
 	data_token: &'tk mut TokenTree2,
 	appends_files: usize,
-	globalposnum: usize,
+	/// The point is the `#POINT_TRACKER_FILES:` marker written by the user,
+	/// the trackers stay there instead of going to the parent point.
+	is_marker: bool,
 }
 
 impl<'tk> PointTrack<'tk> {
 	#[inline]
 	pub const fn new(
-		globalposnum: usize,
+		is_marker: bool,
 		prefix_token: &'tk mut TokenTree2,
 		name_token: &'tk mut TokenTree2,
 		data_token: &'tk mut TokenTree2,
@@ -127,10 +185,15 @@ impl<'tk> PointTrack<'tk> {
 			name_token,
 			data_token,
 			appends_files: 0,
-			globalposnum,
+			is_marker,
 		}
 	}
 
+	#[inline]
+	pub const fn is_marker(&self) -> bool {
+		self.is_marker
+	}
+
 	#[inline]
 	pub fn prefix_span(&self) -> Span {
 		self.prefix_token.span()
@@ -151,30 +214,42 @@ impl<'tk> PointTrack<'tk> {
 		self.appends_files > 0
 	}
 
-	pub fn into_token_tree2(self) -> Option<(usize, TokenTree2)> {
+	pub fn into_token_tree2(self) -> Option<TokenTree2> {
 		match self.appends_files {
 			0 => None,
-			appends_files => {
+			_ => {
 				let data_span = self.data_span();
 
-				Some((
-					appends_files,
-					std::mem::replace(self.data_token, make_null_group(data_span)),
+				Some(std::mem::replace(
+					self.data_token,
+					make_null_group(data_span),
 				))
 			}
 		}
 	}
 
 	pub fn append_track_file(&mut self, path: &Path) {
-		let name_const = format_ident!(
-			"_TRACKER_FILE_NUM_{}",
-			self.globalposnum + self.appends_files
-		);
+		// `include_bytes!` resolves relative paths from the file of the macro call,
+		// so the path is made absolute.
+		let path = match path.canonicalize() {
+			Ok(a) => a,
+			Err(..) => {
+				std::env::current_dir().map_or_else(|_| path.to_path_buf(), |a| a.join(path))
+			}
+		};
+		let path = path.display().to_string();
+		// `const _` can be repeated in one scope, unlike named constants.
+		let ts2 = TokenStream2::from_iter(quote! {
+			const _: &[u8] = include_bytes!(#path);
+		});
+
+		self.append_track_files_ts(ts2)
+	}
 
-		let path = format!("../{}", path.display());
+	pub fn append_track_env(&mut self, name: &str) {
+		// The compiler tracks the variables read by `option_env!`.
 		let ts2 = TokenStream2::from_iter(quote! {
-			/// This is a file tracker point, automatically generated by `#POINT_TRACKER_FILES;`
-			const #name_const: &'static [u8] = include_bytes!(#path) as &[_];
+			const _: Option<&str> = option_env!(#name);
 		});
 
 		self.append_track_files_ts(ts2)
@@ -210,6 +285,44 @@ impl<'tk> PointTrack<'tk> {
 	}
 }
 
+/// Tell the compiler that the result of the macro depends on the file.
+///
+/// (With the `nightly` feature it is tracked by the compiler itself, the
+/// `include_bytes!` constants at `#POINT_TRACKER_FILES:` (or next to the result
+/// of the macro, see `place_trackers`) load every file a second time and remain
+/// the only way on stable)
+pub(crate) fn track_file(point_track: Option<&mut PointTrack>, path: &Path) {
+	#[cfg(feature = "nightly")]
+	if proc_macro::is_available() {
+		let _ = point_track;
+		match path.canonicalize() {
+			Ok(path) => proc_macro::tracked::path(path),
+			Err(..) => proc_macro::tracked::path(path),
+		}
+
+		return;
+	}
+
+	if let Some(point_track) = point_track {
+		point_track.append_track_file(path);
+	}
+}
+
+/// Read the environment variable, telling the compiler that the result
+/// of the macro depends on it (like `track_file`).
+pub(crate) fn tracked_env_var(point_track: Option<&mut PointTrack>, name: &str) -> Option<String> {
+	#[cfg(feature = "nightly")]
+	if proc_macro::is_available() {
+		let _ = point_track;
+		return proc_macro::tracked::env_var(name).ok();
+	}
+
+	if let Some(point_track) = point_track {
+		point_track.append_track_env(name);
+	}
+	std::env::var(name).ok()
+}
+
 impl<'tk> Drop for PointTrack<'tk> {
 	fn drop(&mut self) {
 		if !self.is_rewritten() {
@@ -221,16 +334,51 @@ impl<'tk> Drop for PointTrack<'tk> {
 	}
 }
 
+/// The state of the search, shared by the directives of one macro call.
+pub(crate) struct SearchState<'s, 'tk> {
+	/// Variables declared with `#let` and `#define`.
+	pub vars: &'s mut Vars,
+	/// Files whose contents are being searched, `#tt[recursive]`.
+	pub chain: &'s mut IncludeChain,
+	pub point_track_file: &'s mut Option<PointTrack<'tk>>,
+	/// Errors of the directives, the search goes on after them.
+	pub errs: &'s mut DirectiveErrs,
+}
+
+impl SearchState<'_, '_> {
+	/// Search for directives in the trees of the included file,
+	/// relative paths inside it are relative to this file.
+	pub fn expand_nested(
+		&mut self,
+		path: &Path,
+		mut stream: TokenStream2,
+		span: Span,
+	) -> TreeResult<TokenStream2> {
+		tq!(self.chain.enter(path, span));
+		let result =
+			replace_tree_in_stream(&mut stream, |iter| autoinject_tt_in_subtree(self, iter));
+		self.chain.leave();
+
+		match result {
+			SearchGroup::Break => TreeResult::Ok(stream),
+			SearchGroup::Error(e) => TreeResult::Err(e),
+		}
+	}
+}
+
 /// The task of the function is to find a group with the desired macro
 /// and perform useful work specific to the selected macro.
 ///
 /// The design of this feature has been adapted to search for attachments.
-fn autoinject_tt_in_group<'tk, 'gpsn>(
-	globalposnum: &'gpsn mut usize,
-	mut iter: IterMut<'tk, TokenTree2>,
-	point_track_file: &'_ mut Option<PointTrack<'tk>>,
+fn autoinject_tt_in_group<'tk>(
+	state: &'_ mut SearchState<'_, 'tk>,
+	iter: IterMut<'tk, TokenTree2>,
 ) -> SearchGroup {
+	let mut iter = iter.peekable();
+	// The `#else` block is selected if the previous `#if` block was not selected.
+	let mut else_branch = None;
 	'sbegin: while let Some(m_punct) = iter.next() {
+		let prev_else_branch = else_branch.take();
 		match m_punct {
 			#[cfg(feature = "escape_symbol")]
 			TokenTree2::Punct(punct) if punct.as_char() == '-' => {
@@ -253,6 +401,21 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 			TokenTree2::Punct(punct) if punct.as_char() == '#' => {
 				if let Some(m_ident) = iter.next() {
 					if let TokenTree2::Ident(ident) = m_ident {
+						// The error takes the place of the directive, the search goes on
+						// to report the errors of the other directives as well.
+						macro_rules! inline_err {
+							[ $span:expr, $e:expr ] => {{
+								let span = $span;
+								*m_punct = state.errs.inline($e, span);
+								*m_ident = make_removed_group(m_ident.span());
+								continue 'sbegin;
+							}};
+							[ [$span:expr]: $($err:tt)+ ] => {{
+								let span = $span;
+								inline_err!(span, throw_sg_err! { [span]: $($err)+ })
+							}};
+						}
+
 						#[allow(clippy::type_complexity)]
 						let macro_fn = match &*ident {
 							ident if ident == "AS_IS" => {
@@ -271,42 +434,327 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 									}
 								}
 
-								throw_sg_err! {
-									return [ident.span()]: "`:` was expected."
+								inline_err! {
+									[ident.span()]: "`:` was expected."
 								}
 							}
 							ident if ident == "POINT_TRACKER_FILES" => {
 								if let Some(m_punct2) = iter.next() {
 									if let TokenTree2::Punct(punct2) = m_punct2 {
 										if punct2.as_char() == ':' {
-											*point_track_file = Some(PointTrack::new(
-												*globalposnum,
-												m_punct,
-												m_ident,
-												m_punct2,
-											));
+											let mut point_track_file =
+												PointTrack::new(true, m_punct, m_ident, m_punct2);
+											// The trackers of the files found before the marker are moved to it.
+											if let Some(prev) = state.point_track_file.take() {
+												if let Some(TokenTree2::Group(group)) =
+													prev.into_token_tree2()
+												{
+													point_track_file
+														.append_track_files_ts(group.stream());
+												}
+											}
+											*state.point_track_file = Some(point_track_file);
 
 											continue 'sbegin;
 										}
 									}
 								}
 
-								throw_sg_err! {
-									return [ident.span()]: "`:` was expected."
+								inline_err! {
+									[ident.span()]: "`:` was expected."
 								}
 							}
 							ident if ident == "tt" => {
 								macro_rule_include::<InjectTT>
 									as fn(
 										&Group,
-										Option<&mut PointTrack<'tk>>,
+										Option<&Group>,
+										&mut SearchState<'_, 'tk>,
 									) -> TreeResult<TokenTree2>
 							}
 							ident if ident == "ctt" => macro_rule_include::<InjectCTT> as _,
+							ident if ident == "words" || ident == "lenient" => {
+								macro_rule_include::<InjectWords> as _
+							}
 							ident if ident == "str" => macro_rule_include::<InjectStr> as _,
 							ident if ident == "arr" || ident == "array" => {
 								macro_rule_include::<InjectArr> as _
 							}
+							ident if ident == "assets" => macro_rule_include::<InjectAssets> as _,
+							ident if ident == "c_defines" => {
+								macro_rule_include::<InjectCDefines> as _
+							}
+							ident if ident == "kv" => macro_rule_include::<InjectKv> as _,
+							ident if ident == "lit" => macro_rule_include::<InjectLit> as _,
+							ident if ident == "u8" => macro_rule_include::<InjectU8> as _,
+							ident if ident == "u16" => macro_rule_include::<InjectU16> as _,
+							ident if ident == "u32" => macro_rule_include::<InjectU32> as _,
+							ident if ident == "u64" => macro_rule_include::<InjectU64> as _,
+							ident if ident == "u128" => macro_rule_include::<InjectU128> as _,
+							ident if ident == "usize" => macro_rule_include::<InjectUsize> as _,
+							ident if ident == "i8" => macro_rule_include::<InjectI8> as _,
+							ident if ident == "i16" => macro_rule_include::<InjectI16> as _,
+							ident if ident == "i32" => macro_rule_include::<InjectI32> as _,
+							ident if ident == "i64" => macro_rule_include::<InjectI64> as _,
+							ident if ident == "i128" => macro_rule_include::<InjectI128> as _,
+							ident if ident == "isize" => macro_rule_include::<InjectIsize> as _,
+							ident if ident == "f32" => macro_rule_include::<InjectF32> as _,
+							ident if ident == "f64" => macro_rule_include::<InjectF64> as _,
+							ident if ident == "bool" => macro_rule_include::<InjectBool> as _,
+							ident if ident == "char" => macro_rule_include::<InjectChar> as _,
+							ident if ident == "ident" => macro_rule_include::<InjectIdent> as _,
+							ident if ident == "snake" => macro_rule_include::<InjectSnake> as _,
+							ident if ident == "camel" => macro_rule_include::<InjectCamel> as _,
+							ident if ident == "pascal" => macro_rule_include::<InjectPascal> as _,
+							ident if ident == "shouty" => macro_rule_include::<InjectShouty> as _,
+							ident if ident == "snake_str" => {
+								macro_rule_include::<InjectSnakeStr> as _
+							}
+							ident if ident == "camel_str" => {
+								macro_rule_include::<InjectCamelStr> as _
+							}
+							ident if ident == "pascal_str" => {
+								macro_rule_include::<InjectPascalStr> as _
+							}
+							ident if ident == "shouty_str" => {
+								macro_rule_include::<InjectShoutyStr> as _
+							}
+							ident if ident == "for_each_line" || ident == "for_each_record" => {
+								let source = match ident == "for_each_line" {
+									true => ForEach::Line,
+									false => ForEach::Record,
+								};
+								if let Some((m_flags, m_group)) = next_directive_groups(&mut iter) {
+									let group = state.vars.substitute_group(as_group(m_group));
+									let span = group.span();
+									let mut stream = match macro_rule_for_each(
+										source,
+										&group,
+										m_flags.as_deref().map(as_group),
+										state,
+									) {
+										TreeResult::Ok(a) => a,
+										TreeResult::Err(e) => {
+											TokenStream2::from(state.errs.inline(e, ident.span()))
+										}
+									};
+									// The directives of the template are executed for each repetition.
+									if let result @ SearchGroup::Error(..) =
+										replace_tree_in_stream(&mut stream, |iter| {
+											autoinject_tt_in_subtree(state, iter)
+										}) {
+										return result;
+									}
+
+									*m_ident = make_null_group(m_ident.span());
+									*m_punct = make_null_group(m_punct.span());
+									if let Some(m_flags) = m_flags {
+										*m_flags = make_null_group(m_flags.span());
+									}
+									*m_group = make_flat_group(stream, span);
+
+									continue 'sbegin;
+								}
+
+								inline_err! {
+									[ident.span()]: "After this input, the group `()`, `[]`, `{}` is expected."
+								}
+							}
+							ident if ident == "let" => {
+								// `#let NAME = value;`
+								let (m_name, name) = match iter.peek() {
+									Some(TokenTree2::Ident(name)) if !Vars::is_reserved(name) => {
+										let name = name.clone();
+										(iter.next(), name)
+									}
+									Some(TokenTree2::Ident(name)) => {
+										let span = name.span();
+										remove_to_semicolon(&mut iter);
+										inline_err! {
+											[span]: "The name of the variable is occupied by a directive."
+										}
+									}
+									Some(tt) => {
+										let span = tt.span();
+										remove_to_semicolon(&mut iter);
+										inline_err! {
+											[span]: "The name of the variable was expected: `#let NAME = value;`."
+										}
+									}
+									None => inline_err! {
+										[ident.span()]: "The name of the variable was expected: `#let NAME = value;`."
+									},
+								};
+								if let Some(m_name) = m_name {
+									*m_name = make_removed_group(name.span());
+								}
+								match iter.next_if(|a| is_punct(a, '=')) {
+									Some(tt) => *tt = make_removed_group(tt.span()),
+									None => {
+										remove_to_semicolon(&mut iter);
+										inline_err! {
+											[name.span()]: "`=` was expected after the name of the variable."
+										}
+									}
+								}
+
+								let mut value = Vec::new();
+								loop {
+									match iter.next() {
+										Some(tt) if is_punct(tt, ';') => {
+											*tt = make_removed_group(tt.span());
+											break;
+										}
+										Some(tt) => {
+											value.push(core::mem::replace(
+												tt,
+												make_removed_group(tt.span()),
+											));
+										}
+										None => inline_err! {
+											[name.span()]: "`;` was expected after the value of the variable."
+										},
+									}
+								}
+								if value.is_empty() {
+									inline_err! {
+										[name.span()]: "The value of the variable was expected after `=`."
+									}
+								}
+
+								// The value is computed once: `#let DATA = #str("a.txt");`.
+								let mut value =
+									state.vars.substitute(TokenStream2::from_iter(value));
+								if let result @ SearchGroup::Error(..) =
+									replace_tree_in_stream(&mut value, |iter| {
+										autoinject_tt_in_subtree(state, iter)
+									}) {
+									return result;
+								}
+								state.vars.set(&name, value);
+
+								*m_ident = make_removed_group(m_ident.span());
+								*m_punct = make_removed_group(m_punct.span());
+								continue 'sbegin;
+							}
+							ident if state.vars.get(ident).is_some() => {
+								*m_punct = make_null_group(m_punct.span());
+								if let Some(value) = state.vars.get(ident) {
+									*m_ident = Vars::make_tree(value, ident);
+								}
+								continue 'sbegin;
+							}
+							ident if ident == "define" => {
+								// `#define FLAG;`
+								let span = ident.span();
+								let (m_name, m_end) =
+									match (iter.next(), iter.next_if(|a| is_punct(a, ';'))) {
+										(Some(m_name), Some(m_end)) => (m_name, m_end),
+										(m_name, _) => {
+											if let Some(m_name) = m_name {
+												*m_name = make_removed_group(m_name.span());
+											}
+											inline_err! {
+												[span]: "`#define FLAG;` was expected."
+											}
+										}
+									};
+								*m_end = make_removed_group(m_end.span());
+								match m_name {
+									TokenTree2::Ident(name) if !Vars::is_reserved(name) => {
+										state.vars.set(name, TokenStream2::new())
+									}
+									_ => {
+										let span = m_name.span();
+										*m_name = make_removed_group(span);
+										inline_err! {
+											[span]: "The name of the flag was expected: `#define FLAG;`."
+										}
+									}
+								}
+
+								*m_name = make_removed_group(m_name.span());
+								*m_ident = make_removed_group(m_ident.span());
+								*m_punct = make_removed_group(m_punct.span());
+								continue 'sbegin;
+							}
+							ident if ident == "if" || ident == "ifdef" || ident == "else" => {
+								// The trees between the directive and the block.
+								let mut cond = Vec::new();
+								let m_block = loop {
+									if let Some(m_block) = iter.next_if(
+										|a| matches!(a, TokenTree2::Group(g) if g.delimiter() == Delimiter::Brace),
+									) {
+										break m_block;
+									}
+									match iter.next() {
+										Some(tt) => cond.push(core::mem::replace(
+											tt,
+											make_removed_group(tt.span()),
+										)),
+										None => inline_err! {
+											[ident.span()]: "The block `{ ... }` was expected after the condition."
+										},
+									}
+								};
+
+								let is_selected = match ident {
+									ident if ident == "if" => {
+										eval_condition(&cond, state, ident.span())
+									}
+									ident if ident == "ifdef" => match cond.as_slice() {
+										[TokenTree2::Ident(name)] => {
+											TreeResult::Ok(state.vars.get(name).is_some())
+										}
+										_ => TreeResult::Err(throw_sg_err! {
+											[ident.span()]: "The name of the flag was expected: `#ifdef FLAG { ... }`."
+										}),
+									},
+									ident => match (prev_else_branch, cond.first()) {
+										(Some(is_selected), None) => TreeResult::Ok(is_selected),
+										(_, Some(tt)) => TreeResult::Err(throw_sg_err! {
+											[tt.span()]: "The block `{ ... }` was expected after `#else`."
+										}),
+										(None, None) => TreeResult::Err(throw_sg_err! {
+											[ident.span()]: "`#else` must follow the block of `#if` or `#ifdef`."
+										}),
+									},
+								};
+								let is_selected = match is_selected {
+									TreeResult::Ok(a) => a,
+									TreeResult::Err(e) => {
+										let span = ident.span();
+										// Neither this block nor the `#else` one is selected.
+										if ident != "else" {
+											else_branch = Some(false);
+										}
+										*m_block = make_removed_group(m_block.span());
+										inline_err!(span, e)
+									}
+								};
+								if ident != "else" {
+									else_branch = Some(!is_selected);
+								}
+
+								// Only the selected block is searched, the other one may refer to missing files.
+								*m_block = match (is_selected, &*m_block) {
+									(true, TokenTree2::Group(block)) => {
+										let mut stream = block.stream();
+										if let result @ SearchGroup::Error(..) =
+											replace_tree_in_stream(&mut stream, |iter| {
+												autoinject_tt_in_subtree(state, iter)
+											}) {
+											return result;
+										}
+
+										make_flat_group(stream, block.span())
+									}
+									_ => make_removed_group(m_block.span()),
+								};
+								*m_ident = make_removed_group(m_ident.span());
+								*m_punct = make_removed_group(m_punct.span());
+								continue 'sbegin;
+							}
 							ident if ident == "break" => {
 								/*
 									Stop indexing after the given keyword. This saves resources.
@@ -323,78 +771,82 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 									}
 								}
 
-								throw_sg_err! {
-									return [ident.span()]: "`;` was expected."
+								inline_err! {
+									[ident.span()]: "`;` was expected."
 								}
 							}
 
-							_ => throw_sg_err! {
-								return [ident.span()]: "Undefined action to include data in macro or change its behavior, expected macro data type: `tt`, `ctt`, `arr`, `str`, or marker: `#AS_IS:`, `#POINT_TRACKER_FILES:`, or stop parsing macro via `#break;`."
-							},
-						};
+							_ => {
+								let span = ident.span();
+								let sident = ident.to_string();
+								// The arguments of the unknown directive are removed with it.
+								if let Some((m_flags, m_group)) = next_directive_groups(&mut iter) {
+									if let Some(m_flags) = m_flags {
+										*m_flags = make_removed_group(m_flags.span());
+									}
+									*m_group = make_removed_group(m_group.span());
+								}
 
-						if let Some(m_group) = iter.next() {
-							if let TokenTree2::Group(group) = m_group {
-								let result = tq!(macro_fn(group, point_track_file.as_mut()));
+								let names =
+									RESERVED_NAMES.iter().copied().chain(state.vars.names());
+								if let Some(name) = similar(&sident, names).first() {
+									let sname = String::from(*name);
+									inline_err! {
+										[span]: "Unknown directive `#", #sident, "`, did you mean `#", #sname, "`?"
+									}
+								}
 
-								*m_ident = make_null_group(m_ident.span());
-								*m_punct = make_null_group(m_punct.span());
-								*m_group = result;
+								inline_err! {
+									[span]: "Undefined action to include data in macro or change its behavior, expected macro data type: `tt`, `ctt`, `words`/`lenient`, `arr`, `str`, `lit`, `u8`..`u128`, `i8`..`i128`, `usize`, `isize`, `f32`, `f64`, `bool`, `char`, `assets`, `c_defines`, `kv`, `ident`, `snake`, `camel`, `pascal`, `shouty` (`*_str` for strings), `for_each_line`, `for_each_record`, a variable declared with `#let NAME = value;`, conditions `#if`, `#ifdef`, `#else`, `#define`, or marker: `#AS_IS:`, `#POINT_TRACKER_FILES:`, or stop parsing macro via `#break;`."
+								}
+							}
+						};
 
-								continue 'sbegin;
+						if let Some((m_flags, m_group)) = next_directive_groups(&mut iter) {
+							let result = match macro_fn(
+								&state.vars.substitute_group(as_group(m_group)),
+								m_flags.as_deref().map(as_group),
+								state,
+							) {
+								TreeResult::Ok(a) => a,
+								// The error takes the place of the directive, the search goes on
+								// to report the errors of the other directives as well.
+								TreeResult::Err(e)
+									if IDENT_DIRECTIVES.iter().any(|a| ident == a) =>
+								{
+									state.errs.detach(e, ident.span())
+								}
+								TreeResult::Err(e) => state.errs.inline(e, ident.span()),
+							};
+
+							*m_ident = make_null_group(m_ident.span());
+							*m_punct = make_null_group(m_punct.span());
+							if let Some(m_flags) = m_flags {
+								*m_flags = make_null_group(m_flags.span());
 							}
+							*m_group = result;
+
+							continue 'sbegin;
 						}
-						throw_sg_err! {
-							return [ident.span()]: "After this input, the group `()`, `[]`, `{}` is expected."
+						inline_err! {
+							[ident.span()]: "After this input, the group `()`, `[]`, `{}` is expected."
 						}
 					}
 				}
 			}
 			// If this is a group, then you need to go down inside the
 			// group and look for the necessary macros there.
-			TokenTree2::Group(group) => match replace_tree_in_group(group, |iter| {
-				let mut prefixgroup;
-				let mut namegroup;
-				let mut datagroup;
-				#[allow(clippy::manual_map)] // see ngroup
-				let mut ptf = match point_track_file {
-					Some(point_track_file) => Some({
-						prefixgroup = make_null_group(point_track_file.prefix_span());
-						namegroup = make_null_group(point_track_file.name_span());
-						datagroup = make_null_group(point_track_file.data_span());
-
-						PointTrack::new(
-							*globalposnum,
-							&mut prefixgroup,
-							&mut namegroup,
-							&mut datagroup,
-						)
-					}),
-					None => None,
-				};
-
-				let result = autoinject_tt_in_group(globalposnum, iter, &mut ptf);
-				if let Some(ptf) = ptf {
-					if ptf.is_rewritten() {
-						if let Some(point_track_file) = point_track_file {
-							match ptf.into_token_tree2() {
-								Some((appends_files, TokenTree2::Group(group))) => {
-									*globalposnum += appends_files;
-
-									point_track_file.append_track_files_ts(group.stream());
-								}
-								_ => panic!(
-									"Undefined behavior reported in `PointTrack`, someone redefined `TokenTree2`, expected `TokenTree2::Group`"
-								),
-							}
-						}
-					}
+			TokenTree2::Group(group) => {
+				// `#let` inside the group is not visible after it.
+				state.vars.push_scope();
+				let result =
+					replace_tree_in_group(group, |iter| autoinject_tt_in_subtree(state, iter));
+				state.vars.pop_scope();
+				match result {
+					SearchGroup::Break => continue 'sbegin,
+					result @ SearchGroup::Error(..) => return result,
 				}
-				result
-			}) {
-				SearchGroup::Break => continue 'sbegin,
-				result @ SearchGroup::Error(..) => return result,
-			},
+			}
 			_ => {}
 		}
 	}
@@ -402,14 +854,106 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 	SearchGroup::Break
 }
 
+/// Take the groups of the directive: `[flags](args)` or `(args)`.
+///
+/// (The first group contains flags only if it is `[..]` with nothing but
+/// idents and commas, and is followed by another group: `#tt["a.tt"](1)`
+/// is the path in brackets)
+#[allow(clippy::type_complexity)]
+fn next_directive_groups<'tk>(
+	iter: &mut Peekable<IterMut<'tk, TokenTree2>>,
+) -> Option<(Option<&'tk mut TokenTree2>, &'tk mut TokenTree2)> {
+	let m_group = iter.next_if(|a| matches!(a, TokenTree2::Group(..)))?;
+	let is_flags = matches!(m_group, TokenTree2::Group(g)
+		if g.delimiter() == Delimiter::Bracket
+			&& g.stream().into_iter().all(|a| match a {
+				TokenTree2::Ident(..) => true,
+				TokenTree2::Punct(p) => p.as_char() == ',',
+				_ => false,
+			})
+	);
+
+	match iter.next_if(|a| is_flags && matches!(a, TokenTree2::Group(..))) {
+		Some(m_group2) => Some((Some(m_group), m_group2)),
+		None => Some((None, m_group)),
+	}
+}
+
+/// Get the group from a tree received from `next_directive_groups`.
+#[inline]
+fn as_group(tt: &TokenTree2) -> &Group {
+	match tt {
+		TokenTree2::Group(group) => group,
+		_ => panic!(
+			"Undefined behavior reported in `next_directive_groups`, expected `TokenTree2::Group`"
+		),
+	}
+}
+
+/// Remove the trees of a malformed `#let` up to its `;`, so that only
+/// the error is left in their place.
+fn remove_to_semicolon(iter: &mut Peekable<IterMut<'_, TokenTree2>>) {
+	for tt in iter {
+		let is_end = is_punct(tt, ';');
+		*tt = make_removed_group(tt.span());
+		if is_end {
+			break;
+		}
+	}
+}
+
+/// Search in the trees of a nested stream (group, template),
+/// the found files are tracked at the point of the parent stream.
+fn autoinject_tt_in_subtree(state: &mut SearchState, iter: IterMut<'_, TokenTree2>) -> SearchGroup {
+	let mut prefixgroup;
+	let mut namegroup;
+	let mut datagroup;
+	#[allow(clippy::manual_map)] // see ngroup
+	let mut ptf = match state.point_track_file {
+		Some(point_track_file) => Some({
+			prefixgroup = make_null_group(point_track_file.prefix_span());
+			namegroup = make_null_group(point_track_file.name_span());
+			datagroup = make_null_group(point_track_file.data_span());
+
+			PointTrack::new(false, &mut prefixgroup, &mut namegroup, &mut datagroup)
+		}),
+		None => None,
+	};
+
+	let result = autoinject_tt_in_group(
+		&mut SearchState {
+			vars: state.vars,
+			chain: state.chain,
+			point_track_file: &mut ptf,
+			errs: state.errs,
+		},
+		iter,
+	);
+	if let Some(ptf) = ptf {
+		if ptf.is_rewritten() && !ptf.is_marker() {
+			if let Some(point_track_file) = state.point_track_file {
+				match ptf.into_token_tree2() {
+					Some(TokenTree2::Group(group)) => {
+						point_track_file.append_track_files_ts(group.stream());
+					}
+					_ => panic!(
+						"Undefined behavior reported in `PointTrack`, someone redefined `TokenTree2`, expected `TokenTree2::Group`"
+					),
+				}
+			}
+		}
+	}
+	result
+}
+
 /// Macro for injecting trees, strings, arrays from files.
-/// 
+///
 /// ## template_macro
 /// ```rust
 /// use include_tt::inject;
 /// use std::fmt::Write;
 /// let mut buf = String::new();
-/// 
+///
 /// inject! {
 /// 	write!(
 /// 		&mut buf,
@@ -421,9 +965,9 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 ///
 /// assert_eq!(buf, "Welcome, Ferris. Your score is 100500!");
 /// ```
-/// 
+///
 /// ## basic_codegen
-/// 
+///
 /// ```rust
 /// macro_rules! new_module {
 /// 	[ @($const_t: ident) : [ $($path:tt)* ]; ] => {
@@ -433,19 +977,20 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 /// 			pub mod my_module {
 /// 				pub const a: usize = 0;
 /// 				pub const b: usize = 10;
-/// 				
-/// 				// The `#POINT_TRACKER_FILES:` marker allows the macro to add additional 
-/// 				// instructions that tell the compiler which files to track so that it can 
-/// 				// recompile the macro if they change. This is completely optional, but without 
-/// 				// it tracking will not work.
-/// 				#POINT_TRACKER_FILES: 
-/// 				
+///
+/// 				// The macro adds instructions that tell the compiler which files to track
+/// 				// so that it can recompile the macro if they change. The optional
+/// 				// `#POINT_TRACKER_FILES:` marker sets where they are placed (by default,
+/// 				// before the result of the macro if it is items or statements,
+/// 				// or in a block with it if it is an expression).
+/// 				#POINT_TRACKER_FILES:
+///
 /// 				pub const $const_t: (usize, usize) = (#tt($($path)*));
 /// 			}
 /// 		}
 /// 	};
 /// }
-/// 
+///
 /// // we created a module "my_module" and a constant "T" containing (a, b).
 /// //
 /// // if you need to change, for example, to (b,a) or substitute constant values,
@@ -455,14 +1000,154 @@ fn autoinject_tt_in_group<'tk, 'gpsn>(
 /// }
 /// assert_eq!(my_module::T, (0, 10));
 /// ```
+///
+/// ## Tracking files
+///
+/// On stable, the files are tracked by constants placed before the result
+/// of the macro if it is items or statements, or in a block with it if it is
+/// clearly an expression (`#str("a.txt").len()`, `a + b`, `{ .. }`).
+///
+/// A lone literal (`#str("a.txt")`, `#arr("a.bin")`, which may be the argument
+/// of `concat!` or `include_str!`), a pattern, a type or `impl` items have no
+/// place for them, nor for a warning that they are missing: the macro is not
+/// run again when these files change. Write `#POINT_TRACKER_FILES:` where an
+/// item can be (or use the `nightly` feature) to rebuild on changes:
+///
+/// ```rust
+/// include_tt::inject! {
+/// 	#POINT_TRACKER_FILES:
+/// 	let name = match 100500 {
+/// 		#tt("examples/score.tt") => #tt("examples/name.tt"),
+/// 		_ => "",
+/// 	};
+/// 	assert_eq!(name, "Ferris");
+/// }
+/// ```
+///
+/// ## Errors
+///
+/// A malformed directive is replaced by its error and the search goes on,
+/// so both errors are reported here (the errors of `#ident`, `#snake`.. are
+/// placed before the result if it is items, otherwise only the errors are left):
+///
+/// ```compile_fail
+/// include_tt::inject! {
+/// 	#let = 1;
+/// 	let name = #brek("examples/name.tt");
+/// }
+/// ```
+///
+/// ## Conflicting flags
+///
+/// Only one of `paren`, `brace`, `bracket`, `flat` can wrap the trees:
+///
+/// ```compile_fail
+/// let name = include_tt::inject! { #tt[paren, flat]("examples/name.tt") };
+/// ```
+///
+/// ## Skipped C definitions
+///
+/// `#c_defines` reports the definitions it cannot convert (`UART_REG(n)`) as the use
+/// of a deprecated item, `[quiet]` turns this off:
+///
+/// ```compile_fail
+/// #![deny(deprecated)]
+/// include_tt::inject! { #c_defines("tests/c_defines/regs.h") }
+/// ```
+///
+/// ```rust
+/// #![deny(deprecated)]
+/// include_tt::inject! { #c_defines[quiet]("tests/c_defines/regs.h") }
+/// ```
+///
+/// `#ctt[report]` lists the constructs its preprocessing changed in the same way:
+///
+/// ```compile_fail
+/// #![deny(deprecated)]
+/// include_tt::inject! { #ctt[report]("tests/ctt/items.c") }
+/// ```
 #[proc_macro]
 pub fn inject(input: TokenStream) -> TokenStream {
 	let mut tt: TokenStream2 = input.into();
+	let span = Span::call_site();
 
+	// Trackers of the files found without the `#POINT_TRACKER_FILES:` marker.
+	let mut trackers = None;
+	let mut errs = DirectiveErrs::default();
 	match replace_tree_in_stream(&mut tt, |iter| {
-		autoinject_tt_in_group(&mut 0, iter, &mut None)
+		let mut prefixgroup = make_null_group(span);
+		let mut namegroup = make_null_group(span);
+		let mut datagroup = make_null_group(span);
+		let mut ptf = Some(PointTrack::new(
+			false,
+			&mut prefixgroup,
+			&mut namegroup,
+			&mut datagroup,
+		));
+
+		let result = autoinject_tt_in_group(
+			&mut SearchState {
+				vars: &mut Vars::default(),
+				chain: &mut IncludeChain::default(),
+				point_track_file: &mut ptf,
+				errs: &mut errs,
+			},
+			iter,
+		);
+		if let Some(ptf) = ptf {
+			if !ptf.is_marker() {
+				if let Some(TokenTree2::Group(group)) = ptf.into_token_tree2() {
+					trackers = Some(group.stream());
+				}
+			}
+		}
+		result
 	}) {
-		SearchGroup::Error(e) => e.into(),
-		SearchGroup::Break => tt.into(),
+		SearchGroup::Error(e) => errs.abort(e).into(),
+		SearchGroup::Break => match trackers {
+			Some(trackers) => errs.finish(place_trackers(tt, trackers, span)).into(),
+			None => errs.finish(tt).into(),
+		},
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_inline_directive_errs() {
+	let search = |a: &str| {
+		let mut tt: TokenStream2 = a.parse().unwrap();
+		let result = replace_tree_in_stream(&mut tt, |iter| {
+			autoinject_tt_in_group(
+				&mut SearchState {
+					vars: &mut Vars::default(),
+					chain: &mut IncludeChain::default(),
+					point_track_file: &mut None,
+					errs: &mut DirectiveErrs::default(),
+				},
+				iter,
+			)
+		});
+		assert!(matches!(result, SearchGroup::Break));
+		tt.to_string()
+	};
+
+	// Each malformed directive is replaced by its error, the search goes on.
+	let result =
+		search("#let = 1; #define 1; #nope(a) #if nope(A) { a } #else { b } #ifdef { c } #tt");
+	assert_eq!(result.matches("compile_error").count(), 6, "{result}");
+	for msg in [
+		"The name of the variable was expected",
+		"The name of the flag was expected: `#define FLAG;`",
+		"Undefined action to include data",
+		"Unknown condition",
+		"The name of the flag was expected: `#ifdef FLAG { ... }`",
+		"After this input, the group",
+	] {
+		assert!(result.contains(msg), "{msg}: {result}");
 	}
+	// Neither block of the failed `#if` is selected.
+	assert!(
+		!result.contains("{ a }") && !result.contains("{ b }"),
+		"{result}"
+	);
 }
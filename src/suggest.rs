@@ -0,0 +1,89 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use std::path::Path;
+
+/// The number of single-character edits (insertion, deletion, substitution,
+/// transposition of neighbours) turning `a` into `b`, case-insensitive.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+	let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+
+	// d[i][j] is the distance between `a[..i]` and `b[..j]`.
+	let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in d.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	for (j, a) in d[0].iter_mut().enumerate() {
+		*a = j;
+	}
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			let mut value = (d[i - 1][j - 1] + cost)
+				.min(d[i - 1][j] + 1)
+				.min(d[i][j - 1] + 1);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				value = value.min(d[i - 2][j - 2] + 1);
+			}
+			d[i][j] = value;
+		}
+	}
+
+	d[a.len()][b.len()]
+}
+
+/// Candidates similar to the name, the closest first.
+///
+/// (Up to a third of the name may differ, but at least one character)
+pub fn similar<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+	let max = (name.chars().count() / 3).max(1);
+	let mut result: Vec<(usize, &str)> = candidates
+		.into_iter()
+		.filter(|a| *a != name)
+		.map(|a| (edit_distance(name, a), a))
+		.filter(|(distance, _)| *distance <= max)
+		.collect();
+	result.sort();
+	result.dedup_by(|a, b| a.1 == b.1);
+
+	result.into_iter().map(|(_, a)| a).collect()
+}
+
+/// Files in the directory of the path with names similar to the name of the file.
+pub fn similar_files(path: &Path) -> Vec<String> {
+	let Some(name) = path.file_name().and_then(|a| a.to_str()) else {
+		return Vec::new();
+	};
+	// `a.tt` is searched in the current directory, `Path::new("")`.
+	let dir = path.parent().unwrap_or(Path::new(""));
+	let entries = match dir.as_os_str().is_empty() {
+		true => Path::new(".").read_dir(),
+		false => dir.read_dir(),
+	};
+	let Ok(entries) = entries else {
+		return Vec::new();
+	};
+
+	let names: Vec<String> = entries
+		.filter_map(|a| a.ok()?.file_name().into_string().ok())
+		.collect();
+	similar(name, names.iter().map(String::as_str))
+		.into_iter()
+		.take(3)
+		.map(|a| format!("{:?}", dir.join(a)))
+		.collect()
+}
+
+#[cfg(test)]
+#[test]
+fn test_similar() {
+	assert_eq!(edit_distance("srt", "str"), 1);
+	assert_eq!(edit_distance("arr", "array"), 2);
+	assert_eq!(edit_distance("", "tt"), 2);
+	assert_eq!(edit_distance("CTT", "ctt"), 0);
+
+	let names = ["tt", "ctt", "str", "arr", "array", "assets", "shouty_str"];
+	assert_eq!(similar("srt", names), ["str"]);
+	assert_eq!(similar("t", names), ["tt"]);
+	assert_eq!(similar("aray", names), ["array"]);
+	assert!(similar("xyz", names).is_empty());
+}
@@ -0,0 +1,240 @@
+use crate::{
+	SearchState,
+	exprs::{
+		args::{PathSpan, is_punct, path_span},
+		literal::ExprLit,
+	},
+	formats::csv::parse_csv,
+	throw_sg_err,
+	trees::{
+		group::stream_stringify_with_fns,
+		loader::{LoadFileAndAutoMakeTreeErr, load_file_to_string_with_fns},
+		result::TreeResult,
+		tq,
+	},
+};
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
+use proc_macro2::{Delimiter, Group, Ident, TokenStream as TokenStream2, TokenTree as TokenTree2};
+use std::path::Path;
+
+/// What the template is repeated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForEach {
+	/// Each non-empty line of the file, `#for_each_line`.
+	Line,
+	/// Each record of the CSV file, `#for_each_record`.
+	Record,
+}
+
+/// Parsed arguments of the directive: `path, |$a, $b| { template }`.
+struct ForEachArgs {
+	path: TokenStream2,
+	names: Vec<Ident>,
+	template: TokenStream2,
+}
+
+impl ForEachArgs {
+	fn parse(group: &Group) -> TreeResult<Self> {
+		let all: Vec<TokenTree2> = group.stream().into_iter().collect();
+		let pos = match all.iter().position(|a| is_punct(a, '|')) {
+			Some(a) => a,
+			None => throw_sg_err! {
+				return [group.span()]: "The template was expected after the path: `path, |$line| { ... }`."
+			},
+		};
+		let path = match &all[..pos] {
+			[path @ .., last] if is_punct(last, ',') => path,
+			path => path,
+		};
+
+		let mut names = Vec::new();
+		let mut iter = all[pos + 1..].iter();
+		loop {
+			match (iter.next(), iter.next()) {
+				(Some(dollar), Some(TokenTree2::Ident(name))) if is_punct(dollar, '$') => {
+					names.push(name.clone())
+				}
+				(Some(tt), _) => throw_sg_err! {
+					return [tt.span()]: "The name of the placeholder was expected: `$name`."
+				},
+				(None, _) => throw_sg_err! {
+					return [group.span()]: "The list of placeholders is not closed with `|`."
+				},
+			}
+
+			match iter.next() {
+				Some(tt) if is_punct(tt, ',') => continue,
+				Some(tt) if is_punct(tt, '|') => break,
+				Some(tt) => throw_sg_err! {
+					return [tt.span()]: "`,` or `|` was expected."
+				},
+				None => throw_sg_err! {
+					return [group.span()]: "The list of placeholders is not closed with `|`."
+				},
+			}
+		}
+
+		let template = match (iter.next(), iter.next()) {
+			(Some(TokenTree2::Group(template)), None) => template.stream(),
+			(Some(tt), _) | (None, Some(tt)) => throw_sg_err! {
+				return [tt.span()]: "Exactly one group with the template was expected: `{ ... }`."
+			},
+			(None, None) => throw_sg_err! {
+				return [group.span()]: "The group with the template was expected: `{ ... }`."
+			},
+		};
+
+		TreeResult::Ok(Self {
+			path: TokenStream2::from_iter(path.iter().cloned()),
+			names,
+			template,
+		})
+	}
+}
+
+/// Replace `$name` placeholders in the template with the values.
+///
+/// (A value consisting of several trees is wrapped in an invisible group)
+fn substitute(template: TokenStream2, names: &[Ident], values: &[TokenStream2]) -> TokenStream2 {
+	let mut result = TokenStream2::new();
+
+	let mut iter = template.into_iter().peekable();
+	while let Some(tt) = iter.next() {
+		match tt {
+			TokenTree2::Punct(ref p) if p.as_char() == '$' => {
+				let pos = match iter.peek() {
+					Some(TokenTree2::Ident(i)) => names.iter().position(|a| a == i),
+					_ => None,
+				};
+				match pos {
+					Some(pos) => {
+						iter.next();
+
+						let value = &values[pos];
+						match value.clone().into_iter().count() {
+							1 => result.extend(value.clone()),
+							_ => result.extend([TokenTree2::Group(Group::new(
+								Delimiter::None,
+								value.clone(),
+							))]),
+						}
+					}
+					None => result.extend([tt]),
+				}
+			}
+			TokenTree2::Group(group) => {
+				let mut ngroup =
+					Group::new(group.delimiter(), substitute(group.stream(), names, values));
+				ngroup.set_span(group.span());
+
+				result.extend([TokenTree2::Group(ngroup)]);
+			}
+			tt => result.extend([tt]),
+		}
+	}
+
+	result
+}
+
+/// Build the `#for_each_line`/`#for_each_record` directive: the template is repeated
+/// for each line (record) of the file with the placeholders replaced by its values.
+///
+/// The directives inside the template are not executed here.
+pub fn macro_rule_for_each(
+	source: ForEach,
+	group: &Group,
+	flags: Option<&Group>,
+	state: &mut SearchState,
+) -> TreeResult<TokenStream2> {
+	let span = group.span();
+	let args = tq!(ForEachArgs::parse(group));
+
+	let mut is_header = false;
+	if let Some(flags) = flags {
+		for tt in flags.stream() {
+			match tt {
+				TokenTree2::Ident(i) if source == ForEach::Record && i == "header" => {
+					is_header = true
+				}
+				tt if is_punct(&tt, ',') => {}
+				tt => throw_sg_err! {
+					return [tt.span()]: "Unknown flag, `#for_each_record` supports only `[header]`, `#for_each_line` has no flags."
+				},
+			}
+		}
+	}
+	if source == ForEach::Line && args.names.len() != 1 {
+		throw_sg_err! {
+			return [span]: "`#for_each_line` expects exactly one placeholder: `|$line|`."
+		}
+	}
+
+	let err_span = path_span(&args.path).unwrap_or(PathSpan::from(span));
+	let spath = tq!(stream_stringify_with_fns(
+		args.path,
+		TreeResult::Ok,
+		|| TreeResult::Err(throw_sg_err! {
+			[span]: "The path to the file was expected."
+		}),
+		TreeResult::Err,
+	));
+	let spath = state.chain.resolve(&spath);
+	let path = Path::new(unsafe { ExprLit::new_unchecked(&spath) });
+	let data = tq!(load_file_to_string_with_fns(
+		path,
+		state.point_track_file.as_mut(),
+		TreeResult::Ok,
+		|e| TreeResult::Err(e.into_tt_err(err_span)),
+	));
+	let make_err = |msg: String| {
+		TreeResult::Err(
+			LoadFileAndAutoMakeTreeErr::invalid_content(msg, Cow::Borrowed(path))
+				.into_tt_err(err_span),
+		)
+	};
+
+	// (line number, values)
+	let rows: Vec<(usize, Vec<String>)> = match source {
+		ForEach::Line => data
+			.lines()
+			.enumerate()
+			.filter(|(_, a)| !a.trim().is_empty())
+			.map(|(i, a)| (i + 1, alloc::vec![String::from(a.trim())]))
+			.collect(),
+		ForEach::Record => match parse_csv(&data) {
+			Ok(records) => records
+				.into_iter()
+				.skip(is_header as usize)
+				.map(|a| (a.line, a.fields))
+				.collect(),
+			Err(e) => return make_err(e),
+		},
+	};
+
+	let mut result = TokenStream2::new();
+	for (line, fields) in rows {
+		if fields.len() != args.names.len() {
+			return make_err(format!(
+				"the record on line {line} has {} fields, expected {}",
+				fields.len(),
+				args.names.len()
+			));
+		}
+
+		let mut values = Vec::with_capacity(fields.len());
+		for field in fields {
+			match syn::parse_str::<TokenStream2>(&field) {
+				Ok(a) => values.push(a),
+				Err(e) => {
+					return make_err(format!(
+						"`{field}` on line {line} cannot be converted to trees: {e}"
+					));
+				}
+			}
+		}
+
+		result.extend(substitute(args.template.clone(), &args.names, &values));
+	}
+
+	TreeResult::Ok(result)
+}
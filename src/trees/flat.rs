@@ -0,0 +1,67 @@
+use alloc::vec::Vec;
+use proc_macro2::{
+	Delimiter, Group, Ident, Span, TokenStream as TokenStream2, TokenTree as TokenTree2,
+};
+
+/// The name of the identifier that marks a group to be spliced into its parent.
+const FLAT_MARKER: &str = "__include_tt_flat__";
+
+/// Create a group whose contents will be spliced into the parent stream
+/// without any group (see `flatten_marked_groups`).
+///
+/// The place of a tree can only hold one tree, so splicing is done
+/// after the search is completed.
+pub fn make_flat_group(stream: TokenStream2, span: Span) -> TokenTree2 {
+	let mut ett = TokenStream2::new();
+	ett.extend([TokenTree2::Ident(Ident::new(FLAT_MARKER, span))]);
+	ett.extend(stream);
+
+	let mut ngroup = Group::new(Delimiter::None, ett);
+	ngroup.set_span(span);
+
+	TokenTree2::Group(ngroup)
+}
+
+/// Create a group that will be removed from the parent stream.
+///
+/// (Unlike `make_null_group`, it leaves nothing in place of the tree, which
+/// matters in statement position)
+#[inline]
+pub fn make_removed_group(span: Span) -> TokenTree2 {
+	make_flat_group(TokenStream2::new(), span)
+}
+
+/// If the group was created by `make_flat_group`, return its contents.
+pub fn as_flat_group(group: &Group) -> Option<TokenStream2> {
+	if group.delimiter() != Delimiter::None {
+		return None;
+	}
+
+	let mut iter = group.stream().into_iter();
+	match iter.next() {
+		Some(TokenTree2::Ident(i)) if i == FLAT_MARKER => Some(iter.collect()),
+		_ => None,
+	}
+}
+
+/// Splice the contents of groups created by `make_flat_group` into the stream.
+pub fn flatten_marked_groups(allts: Vec<TokenTree2>) -> Vec<TokenTree2> {
+	let is_flat =
+		|tt: &TokenTree2| matches!(tt, TokenTree2::Group(g) if as_flat_group(g).is_some());
+	if !allts.iter().any(is_flat) {
+		return allts;
+	}
+
+	let mut result = Vec::with_capacity(allts.len());
+	for tt in allts {
+		match tt {
+			TokenTree2::Group(group) => match as_flat_group(&group) {
+				Some(stream) => result.extend(stream),
+				None => result.push(TokenTree2::Group(group)),
+			},
+			tt => result.push(tt),
+		}
+	}
+
+	result
+}
@@ -68,14 +68,7 @@ fn __g_stringify(tt: TokenTree2, w: &mut impl Write) -> TreeResult<()> {
 						}
 					}
 				},
-				|e| {
-					let span = l.span();
-					let debug = e.into_tt_err(span);
-
-					throw_sg_err! {
-						return [span]: "Literal, ", #debug
-					}
-				},
+				|e| TreeResult::Err(e.into_tt_err(l.span())),
 			);
 		}
 	}
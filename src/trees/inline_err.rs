@@ -0,0 +1,197 @@
+use crate::trees::tracker::is_stmts_position;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use proc_macro2::{
+	Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream as TokenStream2,
+	TokenTree as TokenTree2,
+};
+
+/// Turn the errors of a directive (`compile_error!(..);` created by
+/// `throw_sg_err`) into one tree left in place of the directive, so that
+/// the search goes on and the errors of all directives are reported at once.
+///
+/// (`compile_error! { .. }` with braces is valid as an expression, an item,
+/// a statement, a type and a pattern)
+pub fn make_inline_err(e: TokenStream2, span: Span) -> TokenTree2 {
+	let trees: Vec<TokenTree2> = e.clone().into_iter().collect();
+	let mut messages = Vec::new();
+	// `compile_error!` spans from its name to its arguments (see `PathSpan`).
+	let mut msg_span = span;
+	let mut end_span = span;
+	for chunk in trees.chunks(4) {
+		match chunk {
+			[
+				TokenTree2::Ident(name),
+				TokenTree2::Punct(bang),
+				TokenTree2::Group(args),
+				TokenTree2::Punct(semi),
+			] if name == "compile_error" && bang.as_char() == '!' && semi.as_char() == ';' => {
+				if messages.is_empty() {
+					msg_span = name.span();
+					end_span = args.span();
+				}
+				messages.push(args.stream());
+			}
+			_ => {
+				// Not created by `throw_sg_err`, left as is.
+				let mut ngroup = Group::new(Delimiter::None, e);
+				ngroup.set_span(span);
+
+				return TokenTree2::Group(ngroup);
+			}
+		}
+	}
+
+	// Several messages of one directive are joined by lines.
+	let mut message = TokenStream2::new();
+	for (i, a) in messages.into_iter().enumerate() {
+		if i != 0 {
+			message.extend([
+				TokenTree2::Punct(Punct::new(',', Spacing::Alone)),
+				TokenTree2::Literal(Literal::string("\n")),
+				TokenTree2::Punct(Punct::new(',', Spacing::Alone)),
+			]);
+		}
+		message.extend(a);
+	}
+	let mut args = Group::new(Delimiter::Parenthesis, message);
+	args.set_span(msg_span);
+	let mut bang = Punct::new('!', Spacing::Alone);
+	bang.set_span(msg_span);
+	let mut body = Group::new(
+		Delimiter::Brace,
+		TokenStream2::from_iter([
+			TokenTree2::Ident(Ident::new("concat", msg_span)),
+			TokenTree2::Punct(bang.clone()),
+			TokenTree2::Group(args),
+		]),
+	);
+	body.set_span(end_span);
+
+	TokenTree2::Group(Group::new(
+		Delimiter::None,
+		TokenStream2::from_iter([
+			TokenTree2::Ident(Ident::new("compile_error", msg_span)),
+			TokenTree2::Punct(bang),
+			TokenTree2::Group(body),
+		]),
+	))
+}
+
+/// The errors of the directives of one macro call.
+///
+/// (An error stays in place of its directive, except in place of an identifier,
+/// where `compile_error! { .. }` breaks the parsing of the whole result and
+/// hides every message)
+#[derive(Default)]
+pub struct DirectiveErrs {
+	/// All errors, `compile_error!(..);` created by `throw_sg_err`.
+	all: TokenStream2,
+	/// The errors placed before the result of the macro.
+	detached: TokenStream2,
+}
+
+impl DirectiveErrs {
+	/// The error takes the place of the directive (see `make_inline_err`).
+	pub fn inline(&mut self, e: TokenStream2, span: Span) -> TokenTree2 {
+		self.all.extend(e.clone());
+
+		make_inline_err(e, span)
+	}
+
+	/// The error is placed before the result of the macro,
+	/// a placeholder identifier takes the place of the directive.
+	pub fn detach(&mut self, e: TokenStream2, span: Span) -> TokenTree2 {
+		self.all.extend(e.clone());
+		self.detached.extend(e);
+
+		// The placeholders of all macro calls are unique (`let` of one
+		// cannot shadow `struct` of another).
+		static COUNT: AtomicUsize = AtomicUsize::new(0);
+		let n = COUNT.fetch_add(1, Ordering::Relaxed);
+		let name = alloc::format!("__include_tt_error_{n}");
+
+		TokenTree2::Ident(Ident::new(&name, span))
+	}
+
+	/// The result of the macro with the detached errors before it if it is
+	/// items or statements, otherwise only the errors of all directives.
+	pub fn finish(self, stream: TokenStream2) -> TokenStream2 {
+		if self.detached.is_empty() {
+			return stream;
+		}
+
+		match is_stmts_position(&stream) {
+			true => TokenStream2::from_iter([self.detached, stream]),
+			false => self.all,
+		}
+	}
+
+	/// The search was aborted by the error, the result is only the errors.
+	pub fn abort(mut self, e: TokenStream2) -> TokenStream2 {
+		self.all.extend(e);
+
+		self.all
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_make_inline_err() {
+	let span = Span::call_site();
+	let e: TokenStream2 = crate::throw_sg_err! {
+		[span]: "a"
+	};
+	let e2: TokenStream2 = crate::throw_sg_err! {
+		[span]: "b", "c"
+	};
+	let one = make_inline_err(e.clone(), span).to_string();
+	assert_eq!(one, "compile_error ! { concat ! (\"a\") }");
+
+	let two = make_inline_err(TokenStream2::from_iter([e, e2]), span).to_string();
+	assert_eq!(
+		two,
+		"compile_error ! { concat ! (\"a\" , \"\\n\" , concat ! (\"b\" , \"c\")) }"
+	);
+}
+
+#[cfg(test)]
+#[test]
+fn test_directive_errs() {
+	let span = Span::call_site();
+	let e = |a: &str| {
+		quote::quote!(compile_error!(#a);)
+			.into_iter()
+			.collect::<TokenStream2>()
+	};
+
+	// Nothing is detached, the result stays as is.
+	let mut errs = DirectiveErrs::default();
+	let tree = errs.inline(e("a"), span);
+	let result = errs.finish(quote::quote!(let a = #tree;));
+	assert_eq!(
+		result.to_string(),
+		"let a = compile_error ! { concat ! (\"a\") } ;"
+	);
+
+	// Items, the error is placed before them.
+	let mut errs = DirectiveErrs::default();
+	let tree = errs.detach(e("b"), span);
+	let result = errs.finish(quote::quote!(struct #tree;));
+	assert!(
+		result
+			.to_string()
+			.starts_with("compile_error ! (\"b\") ; struct __include_tt_error_"),
+		"{result}"
+	);
+
+	// `fn` can be in `impl`, only the errors are left.
+	let mut errs = DirectiveErrs::default();
+	let tree = errs.inline(e("c"), span);
+	let tree2 = errs.detach(e("d"), span);
+	let result = errs.finish(quote::quote!(fn #tree2() -> u8 { #tree }));
+	assert_eq!(
+		result.to_string(),
+		"compile_error ! (\"c\") ; compile_error ! (\"d\") ;"
+	);
+}
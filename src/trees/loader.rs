@@ -1,9 +1,19 @@
-use alloc::{format, string::String};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
 use proc_macro2::{Span, TokenStream as TokenStream2};
-use std::{borrow::Cow, io::Error as IOError, path::Path};
+use std::{
+	borrow::Cow,
+	io::{Error as IOError, ErrorKind},
+	path::Path,
+};
 use syn::Error as SynError;
 
-use crate::PointTrack;
+use crate::{
+	PointTrack,
+	exprs::{args::PathSpan, kind::SyntaxKind},
+	suggest::similar_files,
+	track_file,
+};
 
 /// Variants of errors when loading a file and presenting it as a set of compiler trees.
 #[derive(Debug)]
@@ -12,8 +22,132 @@ pub enum LoadFileAndAutoMakeTreeErr<'a> {
 	/// [Read], [Write], [Seek], and associated traits.
 	ReadToString { err: IOError, path: Cow<'a, Path> },
 
+	/// The directory cannot be listed.
+	ReadDir { err: IOError, path: Cow<'a, Path> },
+
 	/// Error returned when a Syn parser cannot parse the input tokens.
-	ParseStr(SynError),
+	ParseStr {
+		err: SynError,
+		path: Cow<'a, Path>,
+		location: Option<ErrLocation>,
+	},
+
+	/// The contents of the file do not match the syntax kind required by the directive.
+	ParseKind {
+		kind: SyntaxKind,
+		err: SynError,
+		path: Cow<'a, Path>,
+		location: Option<ErrLocation>,
+	},
+
+	/// The contents of the file do not match what the directive expects.
+	InvalidContent { msg: String, path: Cow<'a, Path> },
+}
+
+/// What went wrong when reading the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileErrKind {
+	NotFound,
+	PermissionDenied,
+	IsADirectory,
+	/// The file was read as a string but is not UTF-8.
+	InvalidUtf8,
+	Other,
+}
+
+impl FileErrKind {
+	/// Classify the I/O error.
+	pub fn new(err: &IOError) -> Self {
+		match err.kind() {
+			ErrorKind::NotFound => Self::NotFound,
+			ErrorKind::PermissionDenied => Self::PermissionDenied,
+			ErrorKind::IsADirectory => Self::IsADirectory,
+			ErrorKind::InvalidData => Self::InvalidUtf8,
+			_ => Self::Other,
+		}
+	}
+}
+
+/// Position of the syntax error in the text of the file.
+#[derive(Debug)]
+pub struct ErrLocation {
+	/// 1-based line.
+	pub line: usize,
+	/// 0-based column in characters.
+	pub column: usize,
+	/// The line of the file, for messages.
+	pub source_line: String,
+}
+
+impl ErrLocation {
+	/// Find the line and the column of the byte position in the text.
+	pub fn new(data: &str, position: usize) -> Self {
+		let before = &data[..position];
+		let line_start = before.rfind('\n').map_or(0, |a| a + 1);
+		let line = before.matches('\n').count() + 1;
+		let column = before[line_start..].chars().count();
+		let source_line = data[line_start..]
+			.lines()
+			.next()
+			.unwrap_or_default()
+			.trim_end()
+			.into();
+
+		Self {
+			line,
+			column,
+			source_line,
+		}
+	}
+
+	/// ` at line 2, column 5` for the first line of the message.
+	pub fn describe(&self) -> String {
+		format!(" at line {}, column {}", self.line, self.column + 1)
+	}
+
+	/// The line with a caret under the column:
+	///
+	/// ```text
+	///   |
+	/// 2 | let a = 1 +;
+	///   |            ^
+	/// ```
+	pub fn excerpt(&self) -> String {
+		let nline = self.line.to_string();
+		let pad = " ".repeat(nline.len());
+		// Tabs are kept so that the caret is under the character.
+		let indent: String = self
+			.source_line
+			.chars()
+			.take(self.column)
+			.map(|a| if a == '\t' { '\t' } else { ' ' })
+			.collect();
+
+		format!(
+			"\n{pad} |\n{nline} | {}\n{pad} | {indent}^",
+			self.source_line
+		)
+	}
+}
+
+/// Help for a file that was not found: where it was searched
+/// and files with similar names.
+fn describe_not_found(path: &Path) -> String {
+	let mut result = String::new();
+	if let Ok(dir) = std::env::current_dir() {
+		let _ = write!(
+			result,
+			"\nThe path was resolved to {:?} from the current directory {:?}.",
+			dir.join(path),
+			dir
+		);
+	}
+	let similar = similar_files(path);
+	if !similar.is_empty() {
+		let _ = write!(result, "\nDid you mean: {}?", similar.join(", "));
+	}
+
+	result
 }
 
 impl<'a> LoadFileAndAutoMakeTreeErr<'a> {
@@ -24,20 +158,104 @@ impl<'a> LoadFileAndAutoMakeTreeErr<'a> {
 		Self::ReadToString { err, path }
 	}
 
+	/// The directory cannot be listed.
+	#[inline]
+	pub const fn read_dir(err: IOError, path: Cow<'a, Path>) -> Self {
+		Self::ReadDir { err, path }
+	}
+
+	/// The contents of the file do not match what the directive expects.
+	#[inline]
+	pub const fn invalid_content(msg: String, path: Cow<'a, Path>) -> Self {
+		Self::InvalidContent { msg, path }
+	}
+
 	/// Convert an error to a syntax tree.
-	pub fn into_tt_err(self, span: Span) -> TokenStream2 {
+	#[inline]
+	pub fn into_tt_err(self, span: impl Into<PathSpan>) -> TokenStream2 {
+		let span = span.into();
+		span.apply(self.make_tt_err(span.first))
+	}
+
+	fn make_tt_err(self, span: Span) -> TokenStream2 {
 		match self {
 			Self::ReadToString { err, path } => {
-				let spath = format!("{path:?}"); // TODO REFACTORME
-				let se = format!("{err:?}");
+				let spath = format!("{path:?}");
+				match FileErrKind::new(&err) {
+					FileErrKind::NotFound => {
+						let shelp = describe_not_found(&path);
+						throw_sg_err! {
+							[span]: "The file ", #spath, " was not found.", #shelp
+						}
+					}
+					FileErrKind::PermissionDenied => throw_sg_err! {
+						[span]: "Permission denied to read the file ", #spath, "."
+					},
+					FileErrKind::IsADirectory => throw_sg_err! {
+						[span]: "The path ", #spath, " is a directory, a file was expected."
+					},
+					FileErrKind::InvalidUtf8 => throw_sg_err! {
+						[span]: "The file ", #spath, " is not valid UTF-8, use `#arr` for binary data or set the encoding: `#str(\"a.txt\"; encoding = \"windows-1252\")`."
+					},
+					FileErrKind::Other => {
+						let se = err.to_string();
+						throw_sg_err! {
+							[span]: "Failed to read the file ", #spath, ": ", #se, "."
+						}
+					}
+				}
+			}
+			Self::ReadDir { err, path } => {
+				let spath = format!("{path:?}");
+				let se = err.to_string();
+				let shelp = match err.kind() {
+					ErrorKind::NotFound => describe_not_found(&path),
+					_ => String::new(),
+				};
 				throw_sg_err! {
-					[span]: "Error loading file, err: '", #se, "', path: ", #spath, "."
+					[span]: "Failed to read the directory ", #spath, ": ", #se, ".", #shelp
 				}
 			}
-			Self::ParseStr(e) => {
-				let se = format!("{e:?}");
+			Self::ParseStr {
+				err,
+				path,
+				location,
+			} => {
+				let spath = format!("{path:?}");
+				let slocation = location
+					.as_ref()
+					.map_or_else(String::new, ErrLocation::describe);
+				let sexcerpt = location
+					.as_ref()
+					.map_or_else(String::new, ErrLocation::excerpt);
+				let se = err.to_string();
 				throw_sg_err! {
-					[span]: "Failed to convert to tree `tt`: '", #se, "'."
+					[span]: "Failed to convert to tree `tt` in ", #spath, #slocation, ": '", #se, "'.", #sexcerpt
+				}
+			}
+			Self::ParseKind {
+				kind,
+				err,
+				path,
+				location,
+			} => {
+				let description = kind.description();
+				let spath = format!("{path:?}");
+				let slocation = location
+					.as_ref()
+					.map_or_else(String::new, ErrLocation::describe);
+				let sexcerpt = location
+					.as_ref()
+					.map_or_else(String::new, ErrLocation::excerpt);
+				let se = err.to_string();
+				throw_sg_err! {
+					[span]: "Expected ", #description, " in ", #spath, #slocation, ": '", #se, "'.", #sexcerpt
+				}
+			}
+			Self::InvalidContent { msg, path } => {
+				let spath = format!("{path:?}");
+				throw_sg_err! {
+					[span]: "Invalid file contents, ", #msg, ", path: ", #spath, "."
 				}
 			}
 		}
@@ -49,19 +267,66 @@ impl<'a> LoadFileAndAutoMakeTreeErr<'a> {
 pub fn load_file_and_automake_tree<'path>(
 	path: &'path Path,
 	point_track: Option<&'_ mut PointTrack>,
+	kind: SyntaxKind,
 
 	// Preprocessing a file loaded into a String before passing it directly to the parser.
 	//
 	// (If this is not required, it is enough to leave the closure empty.)
 	prepare_file_str: impl FnOnce(&mut String),
 ) -> Result<Option<TokenStream2>, LoadFileAndAutoMakeTreeErr<'path>> {
-	load_file_and_automake_tree_with_fns(path, point_track, prepare_file_str, Ok, Err)
+	load_file_and_automake_tree_with_fns(path, point_track, kind, prepare_file_str, Ok, Err)
+}
+
+/// Load the file as bytes.
+pub fn load_file_to_bytes_with_fns<'path, R>(
+	path: &'path Path,
+	point_track: Option<&mut PointTrack>,
+
+	next: impl FnOnce(Vec<u8>) -> R,
+	err: impl FnOnce(LoadFileAndAutoMakeTreeErr<'path>) -> R,
+) -> R {
+	let data = match std::fs::read(path) {
+		Ok(a) => a,
+		Err(e) => {
+			let path = path
+				.canonicalize()
+				.map_or_else(|_| Cow::Borrowed(path), Cow::Owned);
+			return err(LoadFileAndAutoMakeTreeErr::read_to_string(e, path));
+		}
+	};
+	track_file(point_track, path);
+
+	next(data)
+}
+
+/// Load the file as a string.
+pub fn load_file_to_string_with_fns<'path, R>(
+	path: &'path Path,
+	point_track: Option<&mut PointTrack>,
+
+	next: impl FnOnce(String) -> R,
+	err: impl FnOnce(LoadFileAndAutoMakeTreeErr<'path>) -> R,
+) -> R {
+	let data = match std::fs::read_to_string(path) {
+		Ok(a) => a,
+		Err(e) => {
+			let path = path
+				.canonicalize()
+				.map_or_else(|_| Cow::Borrowed(path), Cow::Owned);
+			return err(LoadFileAndAutoMakeTreeErr::read_to_string(e, path));
+		}
+	};
+	track_file(point_track, path);
+
+	next(data)
 }
 
 /// Load the file and present it as a compiler tree set.
 pub fn load_file_and_automake_tree_with_fns<'path, R>(
 	path: &'path Path,
 	point_track: Option<&mut PointTrack>,
+	// What the contents of the file must parse as.
+	kind: SyntaxKind,
 
 	// Preprocessing a file loaded into a String before passing it directly to the parser.
 	//
@@ -71,27 +336,106 @@ pub fn load_file_and_automake_tree_with_fns<'path, R>(
 	next: impl FnOnce(Option<TokenStream2>) -> R,
 	err: impl FnOnce(LoadFileAndAutoMakeTreeErr<'path>) -> R,
 ) -> R {
-	let mut data = match std::fs::read_to_string(path) {
+	let mut data = match load_file_to_string_with_fns(path, point_track, Ok, Err) {
 		Ok(a) => a,
+		Err(e) => return err(e),
+	};
+
+	if data.is_empty() && matches!(kind, SyntaxKind::TokenTrees) {
+		return next(None);
+	}
+
+	prepare_file_str(&mut data);
+
+	match kind.parse_str(&data) {
+		Ok(a) => next(Some(a)),
 		Err(e) => {
+			let location = kind.locate_err(&data).map(|a| ErrLocation::new(&data, a));
 			let path = path
 				.canonicalize()
 				.map_or_else(|_| Cow::Borrowed(path), Cow::Owned);
-			return err(LoadFileAndAutoMakeTreeErr::read_to_string(e, path));
+
+			err(match kind {
+				SyntaxKind::TokenTrees => LoadFileAndAutoMakeTreeErr::ParseStr {
+					err: e,
+					path,
+					location,
+				},
+				kind => LoadFileAndAutoMakeTreeErr::ParseKind {
+					kind,
+					err: e,
+					path,
+					location,
+				},
+			})
 		}
-	};
-	if let Some(point_track) = point_track {
-		point_track.append_track_file(path);
 	}
+}
 
-	if data.is_empty() {
-		return next(None);
-	}
+#[cfg(test)]
+#[test]
+fn test_file_err_kind() {
+	let kind = |path: &str| {
+		load_file_to_string_with_fns(
+			Path::new(path),
+			None,
+			|_| None,
+			|e| match e {
+				LoadFileAndAutoMakeTreeErr::ReadToString { err, .. } => {
+					Some(FileErrKind::new(&err))
+				}
+				_ => None,
+			},
+		)
+	};
 
-	prepare_file_str(&mut data);
+	assert_eq!(kind("./tests/not_found.tt"), Some(FileErrKind::NotFound));
+	// Windows reports a directory as `PermissionDenied`.
+	#[cfg(unix)]
+	assert_eq!(kind("./tests"), Some(FileErrKind::IsADirectory));
+	assert_eq!(kind("./tests/arr/bin.dat"), Some(FileErrKind::InvalidUtf8));
+	assert_eq!(kind("./Cargo.toml"), None);
+}
 
-	match syn::parse_str(&data) {
-		Ok(a) => next(Some(a)),
-		Err(e) => err(LoadFileAndAutoMakeTreeErr::ParseStr(e)),
+#[cfg(test)]
+#[test]
+fn test_err_location() {
+	#[cfg(feature = "syntax_kinds")]
+	{
+		let data = "fn a() {\n\tlet x = 1 +;\n}\n";
+		let location = SyntaxKind::Items
+			.locate_err(data)
+			.map(|a| ErrLocation::new(data, a))
+			.unwrap();
+		assert_eq!(location.describe(), " at line 2, column 13");
+		assert_eq!(
+			location.excerpt(),
+			"\n  |\n2 | \tlet x = 1 +;\n  | \t           ^"
+		);
+
+		let location = SyntaxKind::Expr
+			.locate_err("a +\n  b c")
+			.map(|a| ErrLocation::new("a +\n  b c", a))
+			.unwrap();
+		assert_eq!(location.describe(), " at line 2, column 5");
+
+		// The end of the text.
+		let location = SyntaxKind::Items
+			.locate_err("fn a()\n// b\n")
+			.map(|a| ErrLocation::new("fn a()\n// b\n", a))
+			.unwrap();
+		assert_eq!(location.describe(), " at line 1, column 7");
 	}
+
+	let location = SyntaxKind::TokenTrees
+		.locate_err("a\n `")
+		.map(|a| ErrLocation::new("a\n `", a))
+		.unwrap();
+	assert_eq!(location.describe(), " at line 2, column 2");
+
+	let location = SyntaxKind::TokenTrees
+		.locate_err("a(\n\"b\")]")
+		.map(|a| ErrLocation::new("a(\n\"b\")]", a))
+		.unwrap();
+	assert_eq!(location.describe(), " at line 2, column 5");
 }
@@ -1,3 +1,4 @@
+use crate::trees::flat::flatten_marked_groups;
 use alloc::vec::Vec;
 use core::slice::IterMut;
 use proc_macro2::{Group, TokenStream as TokenStream2, TokenTree as TokenTree2};
@@ -18,6 +19,7 @@ pub fn replace_tree_in_group<R>(
 
 	let mut allts: Vec<TokenTree2> = real_group.stream().into_iter().collect();
 	let result = next(allts.iter_mut());
+	let allts = flatten_marked_groups(allts);
 
 	let mut ngroup = Group::new(delimeter, TokenStream2::from_iter(allts));
 	ngroup.set_span(span);
@@ -38,6 +40,7 @@ pub fn replace_tree_in_stream<R>(
 	let mut allts: Vec<TokenTree2> = core::mem::take(stream).into_iter().collect();
 
 	let result = next(allts.iter_mut());
+	let allts = flatten_marked_groups(allts);
 
 	*stream = TokenStream2::from_iter(allts);
 	result
@@ -0,0 +1,269 @@
+use alloc::vec::Vec;
+use proc_macro2::{Delimiter, Span, TokenStream as TokenStream2, TokenTree as TokenTree2};
+use quote::quote_spanned;
+
+/// Items and statements that are only allowed in modules and blocks,
+/// not in `impl` and `trait` (where `const _` is not allowed either).
+const MODULE_KEYWORDS: &[&str] = &[
+	"mod",
+	"struct",
+	"enum",
+	"union",
+	"trait",
+	"impl",
+	"use",
+	"static",
+	"let",
+	"macro_rules",
+];
+
+/// Associated items of `impl` and `trait` start with them, as well as
+/// items of modules, so they tell nothing about the position.
+const ASSOC_KEYWORDS: &[&str] = &["fn", "const", "type", "async", "extern", "default", "safe"];
+
+/// The trees of the stream with the contents of `None` groups
+/// (the results of directives) in their place.
+fn top_level_trees(stream: TokenStream2, trees: &mut Vec<TokenTree2>) {
+	for tt in stream {
+		match tt {
+			TokenTree2::Group(group) if group.delimiter() == Delimiter::None => {
+				top_level_trees(group.stream(), trees)
+			}
+			tt => trees.push(tt),
+		}
+	}
+}
+
+/// Skip the attributes, the visibility and `unsafe` before the item.
+fn skip_item_prefix(trees: &[TokenTree2]) -> &[TokenTree2] {
+	match trees {
+		[TokenTree2::Punct(p), TokenTree2::Group(g), rest @ ..]
+			if p.as_char() == '#' && g.delimiter() == Delimiter::Bracket =>
+		{
+			skip_item_prefix(rest)
+		}
+		[TokenTree2::Ident(a), TokenTree2::Group(g), rest @ ..]
+			if a == "pub" && g.delimiter() == Delimiter::Parenthesis =>
+		{
+			skip_item_prefix(rest)
+		}
+		[TokenTree2::Ident(a), rest @ ..] if a == "pub" || a == "unsafe" => skip_item_prefix(rest),
+		trees => trees,
+	}
+}
+
+/// `name!(..)` or `a::name!(..)` without anything after it.
+fn is_macro_call(trees: &[TokenTree2]) -> bool {
+	match trees {
+		[path @ .., TokenTree2::Punct(bang), TokenTree2::Group(..)] if bang.as_char() == '!' => {
+			!path.is_empty()
+				&& path.iter().all(|a| match a {
+					TokenTree2::Ident(..) => true,
+					TokenTree2::Punct(p) => p.as_char() == ':',
+					_ => false,
+				})
+		}
+		_ => false,
+	}
+}
+
+/// Whether the result of the macro is clearly items or statements of a
+/// module or a block, where `const _: () = { .. };` can be placed before it.
+///
+/// (A literal, a path, a type or a pattern are never such, and neither are
+/// `fn`, `const`, `type` items and macro calls, which can be in `impl`)
+pub fn is_stmts_position(stream: &TokenStream2) -> bool {
+	let mut trees = Vec::new();
+	top_level_trees(stream.clone(), &mut trees);
+
+	let is_semi = |a: &TokenTree2| matches!(a, TokenTree2::Punct(p) if p.as_char() == ';');
+	// An item or statement that cannot be in `impl`, at the start of the
+	// result or after the end of the previous one.
+	let is_module_item = |trees: &[TokenTree2]| {
+		matches!(skip_item_prefix(trees), [TokenTree2::Ident(a), _, ..]
+			if MODULE_KEYWORDS.iter().any(|k| a == k))
+	};
+	let is_module_start = (0..trees.len())
+		.filter(|&i| {
+			i == 0
+				|| match &trees[i - 1] {
+					TokenTree2::Group(g) => g.delimiter() == Delimiter::Brace,
+					a => is_semi(a),
+				}
+		})
+		.any(|i| is_module_item(&trees[i..]));
+	if is_module_start {
+		return true;
+	}
+
+	// A statement ending with `;` that is not an associated item: `a.b();`.
+	trees
+		.split_inclusive(is_semi)
+		.filter_map(|a| a.split_last())
+		.any(|(last, stmt)| {
+			let stmt = skip_item_prefix(stmt);
+			is_semi(last)
+				&& !stmt.is_empty()
+				&& !matches!(stmt, [TokenTree2::Ident(a), ..] if ASSOC_KEYWORDS.iter().any(|k| a == k))
+				&& !is_macro_call(stmt)
+		})
+}
+
+/// Keywords that only start expressions.
+const EXPR_KEYWORDS: &[&str] = &[
+	"if", "match", "loop", "while", "for", "unsafe", "async", "move", "return", "break", "continue",
+];
+
+/// Whether the result of the macro is clearly an expression, which can be
+/// placed in a block with the trackers: `{ .. }`, `x.len()`, `a + 1`,
+/// `b"a" as &[u8]`, `*b"a"`.
+///
+/// (A lone literal is not, it can be the argument of `concat!` or `include_str!`,
+/// and neither are paths, calls and `|`, `&`, `-`, `..` of patterns and types)
+fn is_expr_position(stream: &TokenStream2) -> bool {
+	let mut trees = Vec::new();
+	top_level_trees(stream.clone(), &mut trees);
+
+	let is_ident = |a: Option<&TokenTree2>, names: &[&str]| match a {
+		Some(TokenTree2::Ident(a)) => names.iter().any(|k| a == k),
+		_ => false,
+	};
+	match trees.as_slice() {
+		[] => return false,
+		[TokenTree2::Group(g)] => return g.delimiter() == Delimiter::Brace,
+		// `for<'a> fn(&'a u8)` is a type.
+		[first, TokenTree2::Punct(p), ..] if first.to_string() == "for" && p.as_char() == '<' => {
+			return false;
+		}
+		[first, ..] if is_ident(Some(first), EXPR_KEYWORDS) => return true,
+		_ => {}
+	}
+
+	let is_type = trees
+		.iter()
+		.any(|a| is_ident(Some(a), &["dyn", "impl", "fn"]));
+	(0..trees.len()).any(|i| {
+		let prev = i.checked_sub(1).map(|i| &trees[i]);
+		let next = trees.get(i + 1);
+		let is_punct = |a: Option<&TokenTree2>, c: char| {
+			matches!(a, Some(TokenTree2::Punct(p)) if p.as_char() == c)
+		};
+		match &trees[i] {
+			TokenTree2::Ident(a) => a == "as",
+			TokenTree2::Punct(p) => match p.as_char() {
+				// `a.b`, not `..`.
+				'.' => {
+					!is_punct(prev, '.')
+						&& !is_punct(next, '.')
+						&& matches!(next, Some(TokenTree2::Ident(..) | TokenTree2::Literal(..)))
+				}
+				// `*a`, `a * b`, not `*const T`.
+				'*' => !is_ident(next, &["const", "mut"]),
+				// `a + b`, not `dyn A + Send`.
+				'+' => !is_type,
+				'/' | '%' | '^' | '?' => true,
+				// `==`, `!=`, not `=>` (a whole `match` arm is not the result).
+				'=' => is_punct(prev, '=') || is_punct(prev, '!'),
+				'&' | '|' => is_punct(prev, p.as_char()),
+				_ => false,
+			},
+			_ => false,
+		}
+	})
+}
+
+/// Place the trackers of files found without the `#POINT_TRACKER_FILES:`
+/// marker: before the result of the macro, `const _: () = { .. };`, if it is
+/// clearly items or statements, or in a block with it if it is clearly an
+/// expression.
+///
+/// (Anything else is left as is: the files of a lone literal, a pattern, a type
+/// or `impl` items are tracked only with `#POINT_TRACKER_FILES:` written where
+/// an item can be, or with the `nightly` feature. Nothing else can be placed
+/// there either, not even a warning about it)
+pub fn place_trackers(stream: TokenStream2, trackers: TokenStream2, span: Span) -> TokenStream2 {
+	if is_stmts_position(&stream) {
+		return quote_spanned! {span=>
+			const _: () = { #trackers };
+			#stream
+		};
+	}
+
+	match is_expr_position(&stream) {
+		true => quote_spanned! {span=>
+			{
+				const _: () = { #trackers };
+				#stream
+			}
+		},
+		false => stream,
+	}
+}
+
+#[cfg(test)]
+#[test]
+fn test_is_stmts_position() {
+	let pos = |a: &str| is_stmts_position(&a.parse().unwrap());
+
+	assert!(pos("pub mod a { pub const A: u8 = 1; }"));
+	assert!(pos("#[derive(Debug)] pub(crate) struct A(u8);"));
+	assert!(pos("impl A { fn a(&self) -> u8 { 1 } }"));
+	assert!(pos("unsafe impl Send for A {}"));
+	assert!(pos("fn a() {} impl A {}"));
+	assert!(pos("let a = 1; a + 2"));
+	assert!(pos("write!(a, \"b\").unwrap();"));
+	assert!(pos("const A: S = S { a: 1 }; let b = 2;"));
+
+	// Literals, expressions, types and patterns.
+	assert!(!pos("7"));
+	assert!(!pos("b\"a\""));
+	assert!(!pos("1 + 2"));
+	assert!(!pos("concat!(\"a\", \"b\")"));
+	assert!(!pos("u8"));
+	assert!(!pos("(u8, [u16; 2])"));
+	assert!(!pos("Some(1) | None"));
+	assert!(!pos("1..=5"));
+	assert!(!pos("{ let a = 1; a }"));
+	assert!(!pos("if let Some(a) = b { a } else { 1 }"));
+	// Can be in `impl`.
+	assert!(!pos("fn a(&self) -> impl Iterator<Item = u8> { 1..2 }"));
+	assert!(!pos("pub const A: S = S { a: 1 }.b();"));
+	assert!(!pos("type A = u8;"));
+	assert!(!pos("name! { a }"));
+	assert!(!pos("a::name!(a);"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_is_expr_position() {
+	let pos = |a: &str| is_expr_position(&a.parse().unwrap());
+
+	assert!(pos("{ const A: u8 = 1; A }"));
+	assert!(pos("\"abc\".len()"));
+	assert!(pos("a + b * 2"));
+	assert!(pos("b\"a\" as &[u8]"));
+	assert!(pos("*b\"a\""));
+	assert!(pos("if a { 1 } else { 2 }"));
+	assert!(pos("a == b && c"));
+	assert!(pos("t.0"));
+
+	// Literals (`concat!`), patterns and types.
+	assert!(!pos("7"));
+	assert!(!pos("\"a\""));
+	assert!(!pos("-1"));
+	assert!(!pos("Some(1) | None"));
+	assert!(!pos("1..=5"));
+	assert!(!pos("&[u8]"));
+	assert!(!pos("*const u8"));
+	assert!(!pos("Box<dyn Fn() + Send>"));
+	assert!(!pos("(u8, u16)"));
+	assert!(!pos("S { a, .. }"));
+	assert!(!pos("concat!(\"a\", \"b\")"));
+	assert!(!pos("for<'a> fn(&'a u8)"));
+
+	let span = Span::call_site();
+	let place = |a: &str| place_trackers(a.parse().unwrap(), quote::quote!(T), span).to_string();
+	assert_eq!(place("a + 1"), "{ const _ : () = { T } ; a + 1 }");
+	assert_eq!(place("let a = 1;"), "const _ : () = { T } ; let a = 1 ;");
+	assert_eq!(place("\"a\""), "\"a\"");
+}
@@ -0,0 +1,171 @@
+use alloc::{string::String, vec::Vec};
+use proc_macro2::{Delimiter, Group, Ident, TokenStream as TokenStream2, TokenTree as TokenTree2};
+
+/// Names of directives and markers that cannot be used as variable names.
+pub const RESERVED_NAMES: &[&str] = &[
+	"tt",
+	"ctt",
+	"words",
+	"lenient",
+	"str",
+	"arr",
+	"array",
+	"assets",
+	"c_defines",
+	"kv",
+	"lit",
+	"u8",
+	"u16",
+	"u32",
+	"u64",
+	"u128",
+	"usize",
+	"i8",
+	"i16",
+	"i32",
+	"i64",
+	"i128",
+	"isize",
+	"f32",
+	"f64",
+	"bool",
+	"char",
+	"ident",
+	"snake",
+	"camel",
+	"pascal",
+	"shouty",
+	"snake_str",
+	"camel_str",
+	"pascal_str",
+	"shouty_str",
+	"for_each_line",
+	"for_each_record",
+	"let",
+	"define",
+	"if",
+	"ifdef",
+	"else",
+	"break",
+	"AS_IS",
+	"POINT_TRACKER_FILES",
+];
+
+/// Macro-local variables declared with `#let NAME = value;`.
+///
+/// A variable is visible after its declaration up to the end of the group
+/// `()`, `[]`, `{}` where it is declared (`#if` blocks and included files
+/// are not groups, they share the scope of the place where they are).
+/// A repeated declaration in the same group replaces the previous value,
+/// in a nested group it shadows the outer one.
+#[derive(Debug, Default)]
+pub struct Vars {
+	list: Vec<(String, TokenStream2)>,
+	/// The length of `list` at the beginning of each nested group.
+	scopes: Vec<usize>,
+}
+
+impl Vars {
+	/// Returns `true` if the name is occupied by a directive or marker.
+	pub fn is_reserved(name: &Ident) -> bool {
+		RESERVED_NAMES.iter().any(|a| name == a)
+	}
+
+	/// Declare the variable, or replace the value of the existing one
+	/// declared in the same group.
+	pub fn set(&mut self, name: &Ident, value: TokenStream2) {
+		let name = name.to_string();
+		let scope = self.scopes.last().copied().unwrap_or_default();
+		match self.list[scope..].iter_mut().find(|(a, _)| *a == name) {
+			Some((_, old)) => *old = value,
+			None => self.list.push((name, value)),
+		}
+	}
+
+	/// Enter a nested group.
+	#[inline]
+	pub fn push_scope(&mut self) {
+		self.scopes.push(self.list.len());
+	}
+
+	/// Leave the nested group, forgetting the variables declared in it.
+	pub fn pop_scope(&mut self) {
+		if let Some(len) = self.scopes.pop() {
+			self.list.truncate(len);
+		}
+	}
+
+	/// Names of the declared variables.
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.list.iter().map(|(a, _)| a.as_str())
+	}
+
+	/// Get the value of the variable by name.
+	pub fn get(&self, name: &Ident) -> Option<&TokenStream2> {
+		self.list
+			.iter()
+			.rfind(|(a, _)| name == a)
+			.map(|(_, value)| value)
+	}
+
+	/// Make a tree from the value of the variable that takes the place of `#NAME`.
+	///
+	/// (A value consisting of several trees is wrapped in an invisible group)
+	pub fn make_tree(value: &TokenStream2, name: &Ident) -> TokenTree2 {
+		let mut iter = value.clone().into_iter();
+		match (iter.next(), iter.next()) {
+			(Some(tt), None) => tt,
+			_ => {
+				let mut ngroup = Group::new(Delimiter::None, value.clone());
+				ngroup.set_span(name.span());
+
+				ngroup.into()
+			}
+		}
+	}
+
+	/// Replace all `#NAME` references to the declared variables in the stream,
+	/// used for the arguments of directives: `#tt(#DIR "/a.tt")`.
+	pub fn substitute(&self, stream: TokenStream2) -> TokenStream2 {
+		if self.list.is_empty() {
+			return stream;
+		}
+
+		let mut result = TokenStream2::new();
+		let mut iter = stream.into_iter().peekable();
+		while let Some(tt) = iter.next() {
+			match tt {
+				TokenTree2::Punct(ref p) if p.as_char() == '#' => {
+					let value = match iter.peek() {
+						Some(TokenTree2::Ident(name)) => self.get(name).map(|a| (a, name.clone())),
+						_ => None,
+					};
+					match value {
+						Some((value, name)) => {
+							iter.next();
+							result.extend([Self::make_tree(value, &name)]);
+						}
+						None => result.extend([tt]),
+					}
+				}
+				TokenTree2::Group(group) => {
+					let mut ngroup = Group::new(group.delimiter(), self.substitute(group.stream()));
+					ngroup.set_span(group.span());
+
+					result.extend([TokenTree2::Group(ngroup)]);
+				}
+				tt => result.extend([tt]),
+			}
+		}
+
+		result
+	}
+
+	/// Same as `substitute`, but for the group of the directive.
+	pub fn substitute_group(&self, group: &Group) -> Group {
+		let mut ngroup = Group::new(group.delimiter(), self.substitute(group.stream()));
+		ngroup.set_span(group.span());
+
+		ngroup
+	}
+}
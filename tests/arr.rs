@@ -0,0 +1,41 @@
+use include_tt::inject;
+
+inject! {
+	#arr("./tests/arr/hi.txt"; shape = array, static = pub GREETING)
+}
+
+macro_rules! lit {
+	($a:literal) => {
+		$a
+	};
+}
+
+const fn len<const N: usize>(_: [u8; N]) -> usize {
+	N
+}
+
+#[test]
+fn test_arr_shape() {
+	let a: &'static [u8; 3] = inject! { #arr("./tests/arr/hi.txt") };
+	assert_eq!(a, b"hi!");
+	let a = inject! { lit!(#arr("./tests/arr/hi.txt")) };
+	assert_eq!(a, b"hi!");
+
+	let a: [u8; 3] = inject! { #arr("./tests/arr/hi.txt"; shape = array) };
+	assert_eq!(a, *b"hi!");
+	const LEN: usize = len(inject! { #arr("./tests/arr/hi.txt"; shape = array) });
+	assert_eq!(LEN, 3);
+
+	const SLICE: &[u8] =
+		inject! { #arr("./tests/arr/hi.txt", "./tests/arr/bin.dat"; shape = slice) };
+	assert_eq!(SLICE, b"hi!\xff\x00");
+
+	let a: &'static str = inject! { #arr("./tests/arr/hi.txt"; shape = str) };
+	assert_eq!(a, "hi!");
+
+	assert_eq!(GREETING, *b"hi!");
+	inject! {
+		#arr("./tests/arr/bin.dat"; shape = slice, static = BIN)
+	}
+	assert_eq!(BIN, b"\xff\x00");
+}
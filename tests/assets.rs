@@ -0,0 +1,32 @@
+use include_tt::inject;
+
+#[test]
+fn test_assets() {
+	let assets = inject! {
+		// Directory contains: `css/copy.css`, `css/site.css`, `data.bin`, `index.html`
+		#assets("./tests/assets")
+	};
+
+	let paths: Vec<&str> = assets.iter().map(|(path, ..)| *path).collect();
+	assert_eq!(
+		paths,
+		["css/copy.css", "css/site.css", "data.bin", "index.html"]
+	);
+
+	let (_, bytes, mime, etag) = assets[3];
+	assert_eq!(bytes, b"<!doctype html>\n<title>index</title>\n");
+	assert_eq!(mime, "text/html; charset=utf-8");
+	assert!(etag.starts_with('"') && etag.ends_with('"'));
+	assert_eq!(etag.len(), 18);
+
+	// Identical content gives an identical `ETag`.
+	assert_eq!(assets[0].2, "text/css; charset=utf-8");
+	assert_eq!(assets[0].3, assets[1].3);
+	assert_ne!(assets[0].3, etag);
+
+	assert_eq!(assets[2].1, &[0, 1, 2]);
+	assert_eq!(assets[2].2, "application/octet-stream");
+
+	let empty = inject!(#assets());
+	assert!(empty.is_empty());
+}
@@ -0,0 +1,36 @@
+// The header of a 32-bit microcontroller, `long` is 32 bits.
+mod regs {
+	include_tt::inject! {
+		#c_defines[quiet]("./tests/c_defines/regs.h"; long = 32)
+	}
+}
+
+// Without `[quiet]`, the skipped `UART_REG(n)` is reported as the use
+// of a deprecated item (see the `compile_fail` example of `inject!`).
+#[allow(deprecated)]
+mod regs_reported {
+	include_tt::inject! {
+		#c_defines("./tests/c_defines/regs.h")
+	}
+}
+
+#[test]
+fn test_c_defines() {
+	use regs::*;
+
+	assert_eq!(UART_BASE, 0x4000_1000_u32);
+	assert_eq!(UART_CTRL, 0x4000_1004_u32);
+	assert_eq!(UART_CTRL_EN | UART_CTRL_IRQ, 0b1001_u32);
+	// `int` wraps as in C.
+	assert_eq!(UART_CTRL_RESET, i32::MIN);
+	assert_eq!(UART_NAME, "uart0");
+	assert_eq!(UART_SEP, b';');
+	assert_eq!(UART_CLOCK, 48_000_000_i32);
+	assert_eq!(UART_SCALE, 1.5_f64);
+	assert_eq!(UART_OFFSET, -16_i32);
+	assert_eq!([UART_MODE_OFF, UART_MODE_RX, UART_MODE_TX], [0_i32, 2, 3]);
+
+	// `long` is 64 bits by default.
+	assert_eq!(regs_reported::UART_BASE, 0x4000_1000_u64);
+	assert_eq!(regs_reported::UART_CTRL_RESET, UART_CTRL_RESET);
+}
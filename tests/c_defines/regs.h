@@ -0,0 +1,25 @@
+#ifndef REGS_H
+#define REGS_H
+
+/* Base address of the peripheral. */
+#define UART_BASE 0x40001000UL
+#define UART_CTRL (UART_BASE + 0x04)
+#define UART_CTRL_EN (1U << 0)
+#define UART_CTRL_IRQ (1U << 3)
+#define UART_CTRL_RESET (1 << 31)
+#define UART_NAME "uart" \
+	"0"
+#define UART_SEP ';'
+#define UART_CLOCK 48000000
+#define UART_SCALE 1.5
+#define UART_OFFSET -16
+
+#define UART_REG(n) (UART_BASE + (n) * 4)
+
+typedef enum {
+	UART_MODE_OFF,
+	UART_MODE_RX = 2,
+	UART_MODE_TX,
+} uart_mode_t;
+
+#endif
@@ -0,0 +1,40 @@
+use include_tt::inject;
+
+macro_rules! make_getters {
+	[ $($name:ident = $value:expr),* $(,)? ] => {
+		include_tt::inject! {
+			$(
+				const #shouty($name): u32 = $value;
+
+				fn #snake(get $name)() -> u32 {
+					#shouty($name)
+				}
+			)*
+
+			const NAMES: &[&str] = &[$( #camel_str($name) ),*];
+		}
+	};
+}
+
+make_getters! {
+	MaxSize = 10,
+	HTTPPort = 20,
+}
+
+#[test]
+fn test_case() {
+	assert_eq!(MAX_SIZE, 10);
+	assert_eq!(get_max_size(), 10);
+	assert_eq!(get_http_port(), 20);
+	assert_eq!(NAMES, ["maxSize", "httpPort"]);
+
+	inject! {
+		struct #pascal("my_" "struct")(u32);
+		let value = #pascal(my _ struct)(1);
+		let str = #pascal_str("my_struct");
+		let snake = #snake_str("MyStruct");
+	}
+	assert_eq!(value.0, 1);
+	assert_eq!(str, "MyStruct");
+	assert_eq!(snake, "my_struct");
+}
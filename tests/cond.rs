@@ -0,0 +1,44 @@
+use include_tt::inject;
+
+inject! {
+	#define FAST;
+
+	#ifdef FAST {
+		const MODE: &str = "fast";
+	} #else {
+		const MODE: &str = "slow";
+	}
+
+	#ifdef SLOW {
+		const SLOW: bool = true;
+	}
+}
+
+#[test]
+fn test_cond() {
+	assert_eq!(MODE, "fast");
+
+	inject! {
+		#let DIR = "./tests/multi/";
+
+		// The file is missing, the block is not searched.
+		let name = #if exists(#DIR "missing.tt") {
+			#tt(#DIR "missing.tt")
+		} #else {
+			#str(#DIR "body.sql")
+		};
+		let a = #if exists("./tests/multi/a.tt") { [#tt(#DIR "a.tt")] } #else { [0, 0] };
+
+		let package = #if env("CARGO_PKG_NAME") == "include_tt" { 1 } #else { 2 };
+		let not_package = #if env("CARGO_PKG_NAME") != "include_tt" { 1 } #else { 2 };
+		let not_env = #if !env("INCLUDE_TT_MISSING_ENV") { true } #else { false };
+		let defined = #if defined(DIR) { true } #else { false };
+	}
+
+	assert_eq!(name, "FROM users");
+	assert_eq!(a, [1, 2]);
+	assert_eq!(package, 1);
+	assert_eq!(not_package, 2);
+	assert!(not_env);
+	assert!(defined);
+}
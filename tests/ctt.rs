@@ -0,0 +1,32 @@
+use include_tt::inject;
+
+// `[report]` lists what the preprocessing changed as the use of a deprecated item.
+#[allow(deprecated)]
+mod reported {
+	include_tt::inject! { #ctt[report]("./tests/ctt/items.c") }
+}
+
+#[allow(deprecated)]
+mod reported_flat {
+	include_tt::inject! { #ctt[report, flat]("./tests/ctt/items.c") }
+}
+
+#[test]
+fn test_ctt_c_source() {
+	const BASE: u32 = 1;
+	let a: u32 = inject! {
+		// `'ab'` is `0x6162`, `'\101'` is `65`.
+		#ctt("./tests/ctt/expr.c")
+	};
+	assert_eq!(a, 1 + 0x6162 + 65);
+
+	// Literals are not altered.
+	let s = inject! { #ctt("./tests/ctt/literal.c") };
+	assert_eq!(s, "a \\ b // c");
+}
+
+#[test]
+fn test_ctt_report() {
+	assert_eq!(reported::MASK, 0x6162);
+	assert_eq!(reported_flat::MASK, 0x6162);
+}
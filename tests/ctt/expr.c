@@ -0,0 +1,5 @@
+#include <stdint.h>
+/** The value, see `MASK`. */
+(BASE + \
+	'ab' /* nested /* comment */ + '\101') // continued \
+	comment
@@ -0,0 +1,2 @@
+/** The mask, see `SHIFT`. */
+pub const MASK: u32 = 'ab';
@@ -0,0 +1,20 @@
+use include_tt::inject;
+
+#[test]
+fn test_str_encoding() {
+	// The BOM is detected without `encoding`.
+	let a = inject! { #str("./tests/encoding/utf16le_bom.txt") };
+	assert_eq!(a, "Größe €");
+
+	let a = inject! { #str("./tests/encoding/utf16be.txt"; encoding = "utf-16be") };
+	assert_eq!(a, "Größe €");
+
+	let a = inject! { #str("./tests/encoding/cp1252.txt"; encoding = "windows-1252") };
+	assert_eq!(a, "“Größe” €");
+
+	let a = inject! { #str("./tests/encoding/latin1.txt"; encoding = "latin1") };
+	assert_eq!(a, "Größe");
+
+	let a = inject! { #str("./tests/encoding/latin1.txt"; encoding = "lossy") };
+	assert_eq!(a, "Gr\u{FFFD}\u{FFFD}e");
+}
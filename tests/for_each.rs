@@ -0,0 +1,31 @@
+use include_tt::inject;
+
+inject! {
+	#[allow(dead_code)]
+	#[derive(Debug, PartialEq)]
+	enum Color {
+		#for_each_line("./tests/for_each/colors.txt", |$name| { $name, })
+	}
+
+	const COLORS: &[&str] = &[
+		#for_each_line("./tests/for_each/colors.txt", |$name| { #snake_str($name), })
+	];
+
+	// Each field is converted to trees: `Mail Server` becomes two identifiers.
+	#for_each_record[header]("./tests/for_each/ports.csv", |$name, $port| {
+		pub const #shouty($name PORT): u16 = $port;
+	})
+}
+
+#[test]
+fn test_for_each() {
+	assert_eq!(COLORS, ["red", "green", "blue"]);
+	assert_eq!(Color::Blue, Color::Blue);
+	assert_eq!(HTTP_SERVER_PORT, 80);
+	assert_eq!(MAIL_SERVER_PORT, 25);
+
+	inject! {
+		let sum = 0 #for_each_record[header]("./tests/for_each/ports.csv", |$name, $port| { + $port });
+	}
+	assert_eq!(sum, 105);
+}
@@ -0,0 +1,26 @@
+use include_tt::inject;
+
+macro_rules! make_handler {
+	[ $name:ident => $result:expr ] => {
+		include_tt::inject! {
+			fn #ident(on "_" $name "_handler")() -> &'static str {
+				$result
+			}
+		}
+	};
+}
+
+make_handler!(click => "click");
+make_handler!(key_down => "key_down");
+
+#[test]
+fn test_ident() {
+	assert_eq!(on_click_handler(), "click");
+	assert_eq!(on_key_down_handler(), "key_down");
+
+	inject! {
+		let #ident(r#type) = 10;
+		let #ident("r#" "mat" "ch") = 20;
+	}
+	assert_eq!(r#type + r#match, 30);
+}
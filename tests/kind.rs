@@ -0,0 +1,28 @@
+#![cfg(feature = "syntax_kinds")]
+
+use include_tt::inject;
+
+inject! {
+	// File contains: `pub const A: u32 = 1; pub fn c() -> u32 { 2 }`
+	#tt(items: "./tests/kind/items.tt")
+}
+
+#[test]
+fn test_kind() {
+	let a = 1;
+	let b = 2;
+	inject! {
+		// File contains: `a + b * 2`
+		let expr = #tt(expr: "./tests/kind/expr.tt");
+		// File contains: `(u32, &'static str)`
+		let ty: #tt(type: "./tests/kind/type.tt") = (1, "1");
+
+		// File contains: `Some(1 | 2) | None`
+		let is_match = matches!(Some(3), #tt(pat: "./tests/kind/pat.tt"));
+	}
+
+	assert_eq!(expr, 5);
+	assert_eq!(ty, (1, "1"));
+	assert!(!is_match);
+	assert_eq!(A + c(), 3);
+}
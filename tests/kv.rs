@@ -0,0 +1,32 @@
+use include_tt::inject;
+
+mod settings {
+	include_tt::inject! {
+		#kv("./tests/kv/settings.env")
+	}
+}
+
+// Lowercase keys do not trigger `non_upper_case_globals`.
+#[deny(non_upper_case_globals)]
+mod lower {
+	include_tt::inject! {
+		#kv("./tests/kv/lower.env")
+	}
+}
+
+#[test]
+fn test_kv() {
+	assert_eq!(settings::HOST, "example.com");
+	assert_eq!(settings::PORT, 8080u16);
+	assert_eq!(settings::URL, "https://example.com:8080/api");
+	assert_eq!(settings::GREETING, "Hello, $USER");
+	const { assert!(!settings::DEBUG) };
+	assert_eq!(settings::RATIO, 0.75);
+	assert_eq!(lower::host, "localhost");
+	assert_eq!(lower::db_port, 5432u16);
+
+	inject! {
+		#kv("./tests/kv/settings.env")
+	}
+	const _: [u8; PORT as usize] = [0; 8080];
+}
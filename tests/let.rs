@@ -0,0 +1,25 @@
+use include_tt::inject;
+
+#[test]
+fn test_let() {
+	inject! {
+		#let DIR = "./tests/multi/";
+		#let HEADER = #str(#DIR "header.sql");
+		#let LIST = 1, 2;
+
+		let header = #HEADER;
+		let body = #str(#DIR "body.sql");
+		let arr = [#LIST, #tt(#DIR "b.tt")];
+		let sum = { #let LIST = 10; #LIST + 1 };
+		// The `#let` of the group above is not visible here.
+		let after = [#LIST];
+		let nested = { #let X = 1; { #let X = 2; } #X };
+	}
+
+	assert_eq!(header, "SELECT *");
+	assert_eq!(body, "FROM users");
+	assert_eq!(arr, [1, 2, 3]);
+	assert_eq!(sum, 11);
+	assert_eq!(after, [1, 2]);
+	assert_eq!(nested, 1);
+}
@@ -0,0 +1,23 @@
+use include_tt::inject;
+
+#[test]
+fn test_multi() {
+	inject! {
+		let sql = #str(
+			"./tests/multi/header.sql",
+			"./tests/multi/body.sql",
+			"./tests/multi/footer.sql";
+			sep = "\n"
+		);
+		let concat = #str("./tests/multi/header.sql", "./tests/multi/body.sql",);
+
+		// Files contain: `1, 2` and `3`
+		let arr = [#tt("./tests/multi/a.tt", "./tests/multi/b.tt"; sep = ,)];
+		let bytes = #arr("./tests/multi/a.tt", "./tests/multi/b.tt"; sep = b';');
+	}
+
+	assert_eq!(sql, "SELECT *\nFROM users\nWHERE id = 1;");
+	assert_eq!(concat, "SELECT *FROM users");
+	assert_eq!(arr, [1, 2, 3]);
+	assert_eq!(bytes, b"1, 2;3");
+}
@@ -0,0 +1 @@
+FROM users
\ No newline at end of file
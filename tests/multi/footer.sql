@@ -0,0 +1 @@
+WHERE id = 1;
\ No newline at end of file
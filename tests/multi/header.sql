@@ -0,0 +1 @@
+SELECT *
\ No newline at end of file
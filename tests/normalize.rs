@@ -0,0 +1,36 @@
+use include_tt::inject;
+
+#[test]
+fn test_str_normalize() {
+	// The file is saved with a BOM, CRLF and indentation (kept without flags).
+	let a = inject! { #str("./tests/normalize/crlf.txt") };
+	assert!(a.starts_with('\u{FEFF}') && a.contains("\r\n"));
+
+	// The encoding is specified, its BOM is not a part of the text.
+	let a = inject! { #str("./tests/normalize/crlf.txt"; encoding = "utf-8") };
+	assert!(a.starts_with("    SELECT"));
+
+	let a = inject! { #str[strip_bom]("./tests/normalize/crlf.txt") };
+	assert!(a.starts_with("    SELECT"));
+
+	let a = inject! { #str[strip_bom, lf, dedent, strip_newline]("./tests/normalize/crlf.txt") };
+	assert_eq!(a, "SELECT *\n  FROM t\nWHERE a = 1");
+
+	let a = inject! { #str[strip_bom, lf, trim]("./tests/normalize/crlf.txt") };
+	assert_eq!(a, "SELECT *\n      FROM t\n    WHERE a = 1");
+}
+
+#[test]
+fn test_tt_normalize() {
+	let a = inject! { #tt[strip_bom, lf]("./tests/normalize/bom.tt") };
+	assert_eq!(a, "multi\nline");
+}
+
+#[test]
+fn test_words_ctt_normalize() {
+	let a = inject! { #words[strip_bom, lf]("./tests/normalize/bom.tt") };
+	assert_eq!(a, "multi\nline");
+
+	let a = inject! { #ctt[strip_bom, lf]("./tests/normalize/bom.tt") };
+	assert_eq!(a, "multi\nline");
+}
@@ -0,0 +1,17 @@
+use include_tt::inject;
+
+#[test]
+fn test_str_raw() {
+	let a = inject! { #str(raw, "./tests/raw/query.sql") };
+	assert_eq!(a, "SELECT \"name\", '#' FROM t\nWHERE a = \"x\"#;\n");
+
+	// `"#` is in the text, so two `#` are needed.
+	let tokens = inject! { stringify!(#str[raw]("./tests/raw/query.sql")) };
+	assert!(tokens.trim().starts_with("r##\"SELECT"), "{tokens}");
+
+	let tokens = inject! { stringify!(#str(raw, "./tests/raw/plain.txt", "./tests/raw/plain.txt"; sep = "\"")) };
+	assert_eq!(tokens.trim(), "r#\"plain\n\"plain\n\"#");
+
+	let tokens = inject! { stringify!(#str("./tests/raw/plain.txt")) };
+	assert_eq!(tokens.trim(), "\"plain\\n\"");
+}
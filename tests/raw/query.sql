@@ -0,0 +1,2 @@
+SELECT "name", '#' FROM t
+WHERE a = "x"#;
@@ -0,0 +1,25 @@
+use include_tt::inject;
+
+inject! {
+	// The names of `#ident` and `#snake` in the nested file are not paths.
+	#tt[recursive]("./tests/recursive/inner/names.tt")
+}
+
+#[test]
+fn test_recursive() {
+	inject! {
+		// Without `[recursive]` the contents of the file are inserted as is.
+		let direct = stringify!(#tt("./tests/recursive/main.tt"));
+		let all = [#tt[recursive]("./tests/recursive/main.tt")];
+	}
+
+	assert!(direct.contains("# tt"));
+	// `three.tt` is relative to `inner/two.tt`, `name.txt` to `inner/`.
+	assert_eq!(all, [1, 2, 3, 6]);
+}
+
+#[test]
+fn test_recursive_names() {
+	assert_eq!(made_fn(), 7);
+	assert_eq!(made_snake_fn(), "made_str");
+}
@@ -0,0 +1,33 @@
+use include_tt::inject;
+
+#[test]
+fn test_scalar() {
+	inject! {
+		// File contains: `100500`
+		let a = #u32("./tests/scalar/u32.tt");
+		// File contains: `0xff`
+		let b = #u8("./tests/scalar/u8.tt");
+		// File contains: `-1_000`
+		let c = #i64("./tests/scalar/i64.tt");
+		// File contains: `2.5`
+		let d = #f64("./tests/scalar/f64.tt");
+		// File contains: `true`
+		let e = #bool("./tests/scalar/bool.tt");
+		// File contains: `Z`
+		let f = #char("./tests/scalar/char.tt");
+
+		// File contains: `"ok"`
+		let g = #lit("./tests/scalar/lit.tt");
+		// File contains: `-7`
+		let h = #lit("./tests/scalar/neg_lit.tt");
+	}
+
+	assert_eq!(a, 100500u32);
+	assert_eq!(b, 255u8);
+	assert_eq!(c, -1000i64);
+	assert_eq!(d, 2.5f64);
+	assert!(e);
+	assert_eq!(f, 'Z');
+	assert_eq!(g, "ok");
+	assert_eq!(h, -7);
+}
@@ -0,0 +1,77 @@
+use include_tt::inject;
+
+// Items, the trackers are placed before them without the marker.
+inject! {
+	const PAIR: (u8, u8) = (#tt("./tests/track/pair.tt"));
+	const ONE: u8 = #tt("./tests/track/one.tt");
+}
+
+// The files are tracked at the marker, several times in nested groups.
+inject! {
+	#POINT_TRACKER_FILES:
+	const PAIRS: [(u8, u8); 2] = [(#tt("./tests/track/pair.tt")), (#tt("./tests/track/pair.tt"))];
+	const TWO: u8 = #tt("./tests/track/one.tt") + #tt("./tests/track/one.tt");
+}
+
+struct A;
+
+impl A {
+	// `const _` is not allowed in `impl`, the trackers are not placed
+	// (`fn` can be an item of a module as well as of `impl`).
+	inject! {
+		fn one(&self) -> u8 {
+			#tt("./tests/track/one.tt")
+		}
+	}
+}
+
+// A type, the trackers are not placed.
+type Ty = inject!(#tt("./tests/track/ty.tt"));
+
+#[test]
+fn test_track() {
+	assert_eq!(PAIR, (1, 2));
+	assert_eq!(ONE, 1);
+	assert_eq!(PAIRS, [(1, 2), (1, 2)]);
+	assert_eq!(TWO, 2);
+	assert_eq!(A.one(), 1);
+	assert_eq!(Ty::MAX, u16::MAX);
+
+	// A literal, the trackers are not placed.
+	let a: u8 = inject! { #tt("./tests/track/one.tt") };
+	assert_eq!(a, 1);
+
+	// Patterns.
+	let name = match 1u8 {
+		inject! { #tt("./tests/track/one.tt") } => "one",
+		2 => "two",
+		_ => "other",
+	};
+	assert_eq!(name, "one");
+	let is_pair = matches!((1u8, 2u8), inject! { #tt[paren]("./tests/track/pair.tt") });
+	assert!(is_pair);
+
+	// Macros that accept only literals.
+	let a = concat!(inject! { #str("./tests/track/one.tt") }, "y");
+	assert_eq!(a, "1y");
+	// `track/one.tt`, relative to this file.
+	let a = include_str!(inject! { #str("./tests/track/path.txt") });
+	assert_eq!(a, "1");
+
+	// Expressions, the trackers are placed in a block with them.
+	let len = inject! { #str("./tests/track/path.txt").len() };
+	assert_eq!(len, 12);
+	let arr = inject! { #arr("./tests/track/one.tt"; shape = array) };
+	assert_eq!(arr, *b"1");
+	let b = inject! { #tt("./tests/track/one.tt") + #tt("./tests/track/one.tt") };
+	assert_eq!(b, 2);
+
+	// Statements with the value of the block at the end.
+	let b = {
+		inject! {
+			let b = #tt("./tests/track/one.tt");
+			b + 1
+		}
+	};
+	assert_eq!(b, 2);
+}
@@ -0,0 +1,88 @@
+//! The messages the compiler shows for the errors of the directives,
+//! `tests/ui/*.rs` are compiled with the built macro.
+
+use std::{path::PathBuf, process::Command};
+
+/// The macro library next to the test binary (the most recently built one).
+fn macro_lib() -> (PathBuf, PathBuf) {
+	let exe = std::env::current_exe().unwrap();
+	let deps = exe.parent().unwrap().to_path_buf();
+	let lib = std::fs::read_dir(&deps)
+		.unwrap()
+		.filter_map(Result::ok)
+		.map(|a| a.path())
+		.filter(|a| {
+			let name = a.file_name().unwrap().to_string_lossy();
+			let is_lib =
+				name.ends_with(".so") || name.ends_with(".dylib") || name.ends_with(".dll");
+			is_lib && name.trim_start_matches("lib").starts_with("include_tt-")
+		})
+		.max_by_key(|a| a.metadata().and_then(|a| a.modified()).unwrap())
+		.expect("the macro library was not found");
+
+	(deps, lib)
+}
+
+/// Compile the file, return the messages of the compiler.
+fn compile_err(file: &str) -> String {
+	let (deps, lib) = macro_lib();
+	let out = std::env::temp_dir().join(format!("include_tt_ui_{}", std::process::id()));
+	let output = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into()))
+		.args([
+			"--edition",
+			"2024",
+			"--crate-type",
+			"lib",
+			"--emit",
+			"metadata",
+		])
+		.arg("--out-dir")
+		.arg(&out)
+		.arg("-L")
+		.arg(format!("dependency={}", deps.display()))
+		.arg("--extern")
+		.arg(format!("include_tt={}", lib.display()))
+		.arg(file)
+		.output()
+		.unwrap();
+	let _ = std::fs::remove_dir_all(&out);
+	assert!(!output.status.success(), "{file} was compiled");
+
+	String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn test_ui_ident_err() {
+	let stderr = compile_err("tests/ui/ident_err.rs");
+
+	for msg in [
+		"`my-fn` is not a valid identifier",
+		"`1st` is not a valid identifier",
+		"`2a` is not a valid identifier",
+	] {
+		assert!(stderr.contains(msg), "{msg}: {stderr}");
+	}
+	// The placeholder of the identifier keeps the result parsable.
+	assert!(!stderr.contains("expected one of"), "{stderr}");
+	assert!(!stderr.contains("error["), "{stderr}");
+	assert!(
+		stderr.contains("aborting due to 3 previous errors"),
+		"{stderr}"
+	);
+}
+
+#[test]
+fn test_ui_leading_flag() {
+	let stderr = compile_err("tests/ui/leading_flag.rs");
+
+	for msg in [
+		"The flag `trim` is written in brackets before the paths: `[trim](\"a.txt\")`.",
+		"The flag `paren` is written in brackets before the paths: `[paren](\"a.txt\")`.",
+	] {
+		assert!(stderr.contains(msg), "{msg}: {stderr}");
+	}
+	assert!(
+		stderr.contains("aborting due to 2 previous errors"),
+		"{stderr}"
+	);
+}
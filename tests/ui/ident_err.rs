@@ -0,0 +1,15 @@
+// The identifier is not valid: only the message of the directive is
+// expected, not the parser errors of `fn` without a name.
+include_tt::inject! {
+	fn #ident(my "-" fn)() -> u8 { 7 }
+}
+
+include_tt::inject! {
+	pub struct #pascal("1" st)(u8);
+}
+
+pub fn f() {
+	include_tt::inject! {
+		let #snake("2" a) = 1;
+	}
+}
@@ -0,0 +1,7 @@
+// Only `raw` of `#str` is accepted before the paths, the other flags are
+// written in brackets.
+pub const A: &str = include_tt::inject! { #str(trim, "./tests/raw/plain.txt", "./tests/raw/plain.txt") };
+
+include_tt::inject! {
+	pub struct B(#tt(paren, "./tests/raw/plain.txt", "./tests/raw/plain.txt"));
+}
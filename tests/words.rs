@@ -0,0 +1,25 @@
+use include_tt::inject;
+
+macro_rules! count_tt {
+	[] => { 0 };
+	[$a:tt $($b:tt)*] => { 1 + count_tt!($($b)*) };
+}
+
+#[test]
+fn test_words_lenient() {
+	// The file contains: say "hello `world` (x]
+	// set 10 {
+	let tokens = inject! { stringify!(#words("./tests/words/dsl.txt")) };
+	assert_eq!(
+		tokens.trim(),
+		"say \"\\\"\" hello \"`\" world \"`\" \"(\" x \"]\" set 10 \"{\""
+	);
+
+	let count: usize = inject! { count_tt!(#lenient("./tests/words/dsl.txt")) };
+	assert_eq!(count, 12);
+
+	// Text accepted by the Rust lexer is included as is.
+	let a = inject! { #words("./tests/expr_lit.tt") };
+	let b = inject! { #tt("./tests/expr_lit.tt") };
+	assert_eq!(a, b);
+}
@@ -0,0 +1,43 @@
+use include_tt::inject;
+
+macro_rules! count_tt {
+	[] => { 0usize };
+	[ $a:tt $($tt:tt)* ] => { 1usize + count_tt!($($tt)*) };
+}
+
+#[test]
+fn test_wrap() {
+	let a = 2;
+	let b = 3;
+	inject! {
+		// File contains: `1, 2`
+		let tuple = #tt[paren]("./tests/wrap/list.tt");
+		let array = #tt[bracket]("./tests/wrap/list.tt");
+		// File contains: `a + b`
+		let block = #tt[brace]("./tests/wrap/sum.tt");
+		let flat = [0, #tt[flat]("./tests/wrap/list.tt"), 3];
+
+		// `flat` inserts the trees of the file without any group.
+		let flat_len = count_tt!(#tt[flat]("./tests/wrap/sum.tt"));
+		let join_len = count_tt!(#tt[flat]("./tests/wrap/sum.tt", "./tests/wrap/sum.tt"; sep = +));
+
+		// Without a following group, the brackets are the path.
+		let str = #str["./tests/wrap/list.tt"];
+
+		// Brackets with a literal are the path even before a group, `add(1, 2)`.
+		let call = #tt["./tests/wrap/fname.tt"](1, 2);
+	}
+
+	assert_eq!(tuple, (1, 2));
+	assert_eq!(array, [1, 2]);
+	assert_eq!(block, 5);
+	assert_eq!(flat, [0, 1, 2, 3]);
+	assert_eq!(flat_len, 3);
+	assert_eq!(join_len, 7);
+	assert_eq!(str, "1, 2");
+	assert_eq!(call, 3);
+}
+
+fn add(a: i32, b: i32) -> i32 {
+	a + b
+}